@@ -0,0 +1,28 @@
+//! Saved n-gram models of german, french and spanish, read by the `language` module of
+//! [cienli](https://crates.io/crates/cienli) with `NgramModel::load`.
+//!
+//! The models hold the counts of the n-grams of the texts of the `corpus` directory of the
+//! repository, written for the crate and distributed under its MIT licence. The texts are folded
+//! with `cienli::language::fold_diacritics` before training, the `train_model` example of cienli
+//! regenerates the models byte for byte.
+
+/// The saved monogram, bigram and quadgram models of german.
+pub const GERMAN: [&[u8]; 3] = [
+    include_bytes!("../models/german1.bin"),
+    include_bytes!("../models/german2.bin"),
+    include_bytes!("../models/german4.bin"),
+];
+
+/// The saved monogram, bigram and quadgram models of french.
+pub const FRENCH: [&[u8]; 3] = [
+    include_bytes!("../models/french1.bin"),
+    include_bytes!("../models/french2.bin"),
+    include_bytes!("../models/french4.bin"),
+];
+
+/// The saved monogram, bigram and quadgram models of spanish.
+pub const SPANISH: [&[u8]; 3] = [
+    include_bytes!("../models/spanish1.bin"),
+    include_bytes!("../models/spanish2.bin"),
+    include_bytes!("../models/spanish4.bin"),
+];
@@ -0,0 +1,47 @@
+//! Trains the n-gram model of a corpus file and saves it, the way the bundled models of the
+//! `language` module are generated. From the root of the repository, every bundled model is
+//! regenerated byte for byte with:
+//!
+//! ```sh
+//! for language in german french spanish; do
+//!     for n in 1 2 4; do
+//!         cargo run --release --example train_model --features language -- \
+//!             cienli-models/corpus/$language.txt $n cienli-models/models/$language$n.bin
+//!     done
+//! done
+//! ```
+//!
+//! The corpus is folded with `fold_diacritics` before training, like the texts scored
+//! by the bundled models.
+use cienli::analysis::NgramModel;
+use cienli::common::Alphabet;
+use cienli::language::fold_diacritics;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, ErrorKind};
+use std::process;
+
+fn main() {
+    let arguments = env::args().skip(1).collect::<Vec<String>>();
+    if arguments.len() != 3 {
+        eprintln!("Usage: train_model <corpus> <n> <output>");
+        process::exit(2);
+    }
+
+    if let Err(error) = train(&arguments[0], &arguments[1], &arguments[2]) {
+        eprintln!("train_model: {}", error);
+        process::exit(1);
+    }
+}
+
+fn train(corpus: &str, n: &str, output: &str) -> io::Result<()> {
+    let n = n
+        .parse::<usize>()
+        .map_err(|error| io::Error::new(ErrorKind::InvalidInput, error))?;
+
+    let corpus = fold_diacritics(&fs::read_to_string(corpus)?);
+    let model = NgramModel::train(&corpus, n, Alphabet::latin())
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+
+    model.save(BufWriter::new(File::create(output)?))
+}
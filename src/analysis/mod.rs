@@ -0,0 +1,386 @@
+//! Statistics of ciphertexts, the foundation for breaking the ciphers.
+//!
+//! The letters are counted the same way the substitution ciphers see them: through an
+//! [`Alphabet`], case-insensitively, and the characters outside of the alphabet are skipped.
+use crate::common::{Alphabet, Case};
+use std::collections::HashMap;
+
+mod ngram;
+
+pub use ngram::NgramModel;
+
+/// Relative frequencies of the letters 'A' to 'Z' in english texts.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// A possible decryption found by a cracker
+///
+/// The score is a log-likelihood, the higher the more likely the plaintext is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate<K> {
+    pub key: K,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+/// Sorts the candidates from the most likely to the least likely.
+pub fn rank<K>(candidates: &mut [Candidate<K>]) {
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+/// Returns how much a text looks like english, as the average log-likelihood of its characters.
+///
+/// The letters are scored by [`ENGLISH_FREQUENCIES`] (uppercase letters being rarer than
+/// lowercase ones), spaces and punctuation by their usual share of english texts, and other
+/// characters are heavily penalized. The higher the better.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::english_score;
+///
+/// assert!(english_score("Defend the east wall") > english_score("Ijkjsi ymj jfxy bfqq"));
+/// assert!(english_score("Defend the east wall") > english_score("s676?5 E96 62DE H2=="));
+/// ```
+pub fn english_score(text: &str) -> f64 {
+    let mut total = 0.0;
+    let mut count = 0;
+
+    for character in text.chars() {
+        let probability = match character {
+            'a'..='z' => 0.7 * ENGLISH_FREQUENCIES[character as usize - 97],
+            'A'..='Z' => 0.05 * ENGLISH_FREQUENCIES[character as usize - 65],
+            ' ' => 0.17,
+            '\n' | '\r' | '\t' => 0.01,
+            '.' | ',' | '\'' | '"' | '-' | '!' | '?' | ';' | ':' => 0.005,
+            '0'..='9' => 0.001,
+            _ if character.is_ascii_graphic() => 0.0002,
+            _ => 0.00001,
+        };
+
+        total += probability.log10();
+        count += 1;
+    }
+
+    match count {
+        0 => f64::NEG_INFINITY,
+        _ => total / count as f64,
+    }
+}
+
+/// Returns the positions in the alphabet of the letters of a text, other characters are skipped.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::letters;
+/// use cienli::common::Alphabet;
+///
+/// assert_eq!(vec![7, 8], letters("Hi!", &Alphabet::latin()).collect::<Vec<usize>>());
+/// ```
+pub fn letters<'a>(text: &'a str, alphabet: &'a Alphabet) -> impl Iterator<Item = usize> + 'a {
+    text.chars()
+        .filter_map(move |character| alphabet.index_of(character))
+        .map(|(index, _)| index)
+}
+
+/// Counts every letter of the alphabet in a text, the counts are ordered like the alphabet.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::letter_counts;
+/// use cienli::common::Alphabet;
+///
+/// let counts = letter_counts("Abba!", &Alphabet::latin());
+/// assert_eq!(&[2, 2, 0], &counts[..3]);
+/// ```
+pub fn letter_counts(text: &str, alphabet: &Alphabet) -> Vec<usize> {
+    let mut counts = vec![0; alphabet.len()];
+
+    for index in letters(text, alphabet) {
+        counts[index] += 1;
+    }
+
+    counts
+}
+
+/// Returns the relative frequency of every letter of the alphabet in a text.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::letter_frequencies;
+/// use cienli::common::Alphabet;
+///
+/// let frequencies = letter_frequencies("Abba!", &Alphabet::latin());
+/// assert_eq!(&[0.5, 0.5, 0.0], &frequencies[..3]);
+/// ```
+pub fn letter_frequencies(text: &str, alphabet: &Alphabet) -> Vec<f64> {
+    let counts = letter_counts(text, alphabet);
+    let total = counts.iter().sum::<usize>();
+
+    counts
+        .iter()
+        .map(|count| match total {
+            0 => 0.0,
+            _ => *count as f64 / total as f64,
+        })
+        .collect()
+}
+
+/// Counts the n-grams of the letters of a text, the characters outside of the alphabet
+/// are skipped and the n-grams are written in uppercase.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::ngram_counts;
+/// use cienli::common::Alphabet;
+///
+/// let counts = ngram_counts("Hello, hello", 2, &Alphabet::latin());
+/// assert_eq!(Some(&2), counts.get("LL"));
+/// assert_eq!(Some(&1), counts.get("OH"));
+/// ```
+pub fn ngram_counts(text: &str, n: usize, alphabet: &Alphabet) -> HashMap<String, usize> {
+    let letters = letters(text, alphabet)
+        .map(|index| alphabet.letter(index, Case::Upper))
+        .collect::<Vec<char>>();
+
+    let mut counts = HashMap::new();
+    if n == 0 {
+        return counts;
+    }
+
+    for window in letters.windows(n) {
+        *counts.entry(window.iter().collect::<String>()).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+/// Returns the relative frequency of the n-grams of the letters of a text.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::ngram_frequencies;
+/// use cienli::common::Alphabet;
+///
+/// let frequencies = ngram_frequencies("abab", 2, &Alphabet::latin());
+/// assert_eq!(Some(&(2.0 / 3.0)), frequencies.get("AB"));
+/// ```
+pub fn ngram_frequencies(text: &str, n: usize, alphabet: &Alphabet) -> HashMap<String, f64> {
+    let counts = ngram_counts(text, n, alphabet);
+    let total = counts.values().sum::<usize>() as f64;
+
+    counts
+        .into_iter()
+        .map(|(ngram, count)| (ngram, count as f64 / total))
+        .collect()
+}
+
+/// Returns the relative frequency of the bigrams of the letters of a text.
+pub fn bigram_frequencies(text: &str, alphabet: &Alphabet) -> HashMap<String, f64> {
+    ngram_frequencies(text, 2, alphabet)
+}
+
+/// Returns the relative frequency of the trigrams of the letters of a text.
+pub fn trigram_frequencies(text: &str, alphabet: &Alphabet) -> HashMap<String, f64> {
+    ngram_frequencies(text, 3, alphabet)
+}
+
+/// Returns the index of coincidence of the letters of a text, the probability that two
+/// letters drawn at random are the same.
+///
+/// It is about 0.066 for english and 1/26 (0.038) for uniformly random letters.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::index_of_coincidence;
+/// use cienli::common::Alphabet;
+///
+/// assert_eq!(1.0, index_of_coincidence("aaaa", &Alphabet::latin()));
+/// assert_eq!(0.0, index_of_coincidence("abcd", &Alphabet::latin()));
+/// ```
+pub fn index_of_coincidence(text: &str, alphabet: &Alphabet) -> f64 {
+    let counts = letter_counts(text, alphabet);
+    let total = counts.iter().sum::<usize>();
+
+    if total < 2 {
+        return 0.0;
+    }
+
+    let coincidences = counts
+        .iter()
+        .map(|count| count * count.saturating_sub(1))
+        .sum::<usize>();
+
+    coincidences as f64 / (total * (total - 1)) as f64
+}
+
+/// Returns the chi-squared distance between the letters of a text and a reference
+/// distribution (e.g. [`ENGLISH_FREQUENCIES`]), the lower the closer.
+///
+/// The reference distribution is ordered like the alphabet, the letters with an
+/// expected frequency of zero are ignored.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::{chi_squared, ENGLISH_FREQUENCIES};
+/// use cienli::common::Alphabet;
+///
+/// let english = chi_squared("Defend the east wall of the castle", &Alphabet::latin(), &ENGLISH_FREQUENCIES);
+/// let cipher = chi_squared("Ijkjsi ymj jfxy bfqq tk ymj hfxyqj", &Alphabet::latin(), &ENGLISH_FREQUENCIES);
+/// assert!(english < cipher);
+/// ```
+pub fn chi_squared(text: &str, alphabet: &Alphabet, expected: &[f64]) -> f64 {
+    let counts = letter_counts(text, alphabet);
+    chi_squared_counts(&counts, expected)
+}
+
+/// Returns the chi-squared distance between letter counts and a reference distribution.
+pub fn chi_squared_counts(counts: &[usize], expected: &[f64]) -> f64 {
+    let total = counts.iter().sum::<usize>() as f64;
+
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    counts
+        .iter()
+        .zip(expected.iter())
+        .filter(|(_, frequency)| **frequency > 0.0)
+        .map(|(count, frequency)| {
+            let expected_count = total * frequency;
+            (*count as f64 - expected_count).powi(2) / expected_count
+        })
+        .sum()
+}
+
+/// Returns the Shannon entropy of the characters of a text in bits per character.
+///
+/// Unlike the other functions, every character of the text is taken into account.
+///
+/// # Example:
+/// ```
+/// use cienli::analysis::entropy;
+///
+/// assert_eq!(0.0, entropy("aaaa"));
+/// assert_eq!(2.0, entropy("abcd"));
+/// ```
+pub fn entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for character in text.chars() {
+        *counts.entry(character).or_insert(0) += 1;
+    }
+
+    let total = counts.values().sum::<usize>() as f64;
+
+    counts
+        .values()
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum::<f64>()
+        .abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGLISH: &str =
+        "The morning train to the coast was late again, so the passengers waited \
+        on the cold platform and watched the rain fall on the empty tracks. A porter pushed a cart \
+        of parcels past the benches, and a small dog barked at the pigeons that gathered under the \
+        roof. When the train finally arrived, the guard apologised to everyone and promised that \
+        the journey would be quick.";
+
+    #[test]
+    fn english_frequencies_sum_test() {
+        assert!((ENGLISH_FREQUENCIES.iter().sum::<f64>() - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn rank_test() {
+        let mut candidates = vec![
+            Candidate {
+                key: 1,
+                plaintext: String::from("Ebiil"),
+                score: english_score("Ebiil"),
+            },
+            Candidate {
+                key: 2,
+                plaintext: String::from("Hello"),
+                score: english_score("Hello"),
+            },
+        ];
+        rank(&mut candidates);
+
+        assert_eq!(2, candidates[0].key);
+        assert_eq!(f64::NEG_INFINITY, english_score(""));
+    }
+
+    #[test]
+    fn letter_counts_test() {
+        let counts = letter_counts("Привет, мир!", &Alphabet::cyrillic());
+
+        assert_eq!(33, counts.len());
+        assert_eq!(2, counts[Alphabet::cyrillic().index_of('и').unwrap().0]);
+        assert_eq!(9, counts.iter().sum::<usize>());
+    }
+
+    #[test]
+    fn ngram_skips_non_letters_test() {
+        let counts = ngram_counts("ab, c", 3, &Alphabet::latin());
+
+        assert_eq!(1, counts.len());
+        assert_eq!(Some(&1), counts.get("ABC"));
+        assert!(ngram_counts("ab", 3, &Alphabet::latin()).is_empty());
+        assert!(ngram_counts("ab", 0, &Alphabet::latin()).is_empty());
+    }
+
+    #[test]
+    fn trigram_frequencies_test() {
+        let frequencies = trigram_frequencies(ENGLISH, &Alphabet::latin());
+        let most_common = frequencies.values().cloned().fold(0.0, f64::max);
+
+        assert!((frequencies.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(Some(&most_common), frequencies.get("THE"));
+    }
+
+    #[test]
+    fn index_of_coincidence_test() {
+        let english = index_of_coincidence(ENGLISH, &Alphabet::latin());
+        let uniform = index_of_coincidence(
+            "abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz",
+            &Alphabet::latin(),
+        );
+
+        assert!(english > 0.06);
+        assert!(uniform < 0.02);
+        assert_eq!(0.0, index_of_coincidence("a", &Alphabet::latin()));
+    }
+
+    #[test]
+    fn chi_squared_test() {
+        let english = chi_squared(ENGLISH, &Alphabet::latin(), &ENGLISH_FREQUENCIES);
+        let shifted = chi_squared(
+            "Wkh pruqlqj wudlq wr wkh frdvw zdv odwh djdlq",
+            &Alphabet::latin(),
+            &ENGLISH_FREQUENCIES,
+        );
+
+        assert!(english < shifted);
+        assert_eq!(
+            0.0,
+            chi_squared("", &Alphabet::latin(), &ENGLISH_FREQUENCIES)
+        );
+    }
+
+    #[test]
+    fn entropy_test() {
+        assert_eq!(0.0, entropy(""));
+        assert_eq!(1.0, entropy("abab"));
+        assert!(entropy(ENGLISH) > 3.0);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::analysis::letters;
+use crate::common::{Alphabet, Case};
+use crate::Error;
+use std::convert::TryInto;
+use std::fs;
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Public domain english texts the english models are trained on: the Gettysburg Address,
+/// the Declaration of Independence, the Preamble and First Amendment of the Constitution,
+/// Lincoln's second inaugural address, the openings of A Tale of Two Cities, Pride and Prejudice,
+/// Moby-Dick, Alice's Adventures in Wonderland, A Christmas Carol, Anna Karenina (translated by
+/// Constance Garnett), A Scandal in Bohemia, Frankenstein and The Great Gatsby, followed by
+/// texts written for the crate and distributed under its MIT licence.
+const ENGLISH_CORPUS: &str = include_str!("english.txt");
+
+/// The largest number of n-grams a model can hold.
+const MAX_TABLE_SIZE: usize = 1 << 24;
+
+/// The first bytes of a saved model.
+const MAGIC: &[u8; 4] = b"CNGM";
+
+/// N-gram language model
+///
+/// The model holds the log-probability of every n-gram of letters of an alphabet, the
+/// n-grams that never appear in the corpus get a floor probability lower than the rarest ones,
+/// lowered further when their shorter n-grams are rare.
+/// The struct is generated through the train() function or the english models.
+#[derive(Debug, Clone)]
+pub struct NgramModel {
+    n: usize,
+    alphabet: Alphabet,
+    /// The table index and count of the n-grams seen in the corpus, for every length up to n.
+    counts: Vec<Vec<(u32, u32)>>,
+    log_probabilities: Vec<f64>,
+}
+
+impl NgramModel {
+    /// Trains a model on the n-grams of the letters of a corpus, the characters outside
+    /// of the alphabet are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    /// use cienli::common::Alphabet;
+    ///
+    /// let model = NgramModel::train("the cat sat on the mat", 2, Alphabet::latin()).unwrap();
+    /// assert!(model.score("that") > model.score("xqzj"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The n-gram length must not be zero, the table of the n-grams must not be too large
+    /// and the corpus must contain at least one n-gram and less than 2^32 letters.
+    pub fn train(corpus: &str, n: usize, alphabet: Alphabet) -> Result<NgramModel, Error> {
+        table_size(n, &alphabet)?;
+
+        let letters = letters(corpus, &alphabet).collect::<Vec<usize>>();
+        if letters.len() < n {
+            return Err(Error::InvalidKey(
+                "The corpus must contain at least one n-gram",
+            ));
+        }
+        if letters.len() > u32::MAX as usize {
+            return Err(Error::InvalidKey("The corpus is too large"));
+        }
+
+        let counts = (1..=n)
+            .map(|length| count(&letters, length, alphabet.len()))
+            .collect();
+
+        Ok(NgramModel::from_counts(n, alphabet, counts))
+    }
+
+    /// Trains a model on the n-grams of the letters of a corpus file.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    /// use cienli::common::Alphabet;
+    ///
+    /// let path = std::env::temp_dir().join("cienli_train_file_example.txt");
+    /// std::fs::write(&path, "the cat sat on the mat").unwrap();
+    ///
+    /// let model = NgramModel::train_file(&path, 2, Alphabet::latin()).unwrap();
+    /// assert!(model.score("that") > model.score("xqzj"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// # Errors:
+    /// The file must be readable UTF-8 text, and the same requirements as train() apply,
+    /// reported as invalid data.
+    pub fn train_file<P: AsRef<Path>>(
+        path: P,
+        n: usize,
+        alphabet: Alphabet,
+    ) -> io::Result<NgramModel> {
+        let corpus = fs::read_to_string(path)?;
+        NgramModel::train(&corpus, n, alphabet).map_err(invalid_data)
+    }
+
+    /// Writes the model in a compact binary format: the "CNGM" magic bytes, the length of
+    /// the n-grams, the uppercase and lowercase letters of the alphabet, then for every length
+    /// up to n the number of n-grams seen in the corpus followed by their table index and count,
+    /// all integers being little-endian u32.
+    ///
+    /// Only the counts are saved, load() computes the log-probabilities the way train() does.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    ///
+    /// let model = NgramModel::english_bigrams();
+    /// let mut bytes = Vec::new();
+    /// model.save(&mut bytes).unwrap();
+    ///
+    /// let loaded = NgramModel::load(bytes.as_slice()).unwrap();
+    /// assert_eq!(model.score("Attack at dawn"), loaded.score("Attack at dawn"));
+    /// ```
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let entries = self.counts.iter().map(Vec::len).sum::<usize>();
+        let mut bytes = Vec::with_capacity(16 + 4 * self.n + 8 * entries);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&(self.n as u32).to_le_bytes());
+
+        for case in [Case::Upper, Case::Lower] {
+            let letters = (0..self.alphabet.len())
+                .map(|index| self.alphabet.letter(index, case))
+                .collect::<String>();
+            bytes.extend_from_slice(&(letters.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(letters.as_bytes());
+        }
+
+        for seen in self.counts.iter() {
+            bytes.extend_from_slice(&(seen.len() as u32).to_le_bytes());
+            for (index, count) in seen.iter() {
+                bytes.extend_from_slice(&index.to_le_bytes());
+                bytes.extend_from_slice(&count.to_le_bytes());
+            }
+        }
+
+        writer.write_all(&bytes)
+    }
+
+    /// Reads a model written by save().
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    ///
+    /// assert!(NgramModel::load(&b"not a model"[..]).is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The data must be a complete model, anything else is reported as invalid data.
+    pub fn load<R: Read>(mut reader: R) -> io::Result<NgramModel> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let mut bytes = bytes.as_slice();
+
+        if take(&mut bytes, MAGIC.len())? != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "The data is not an n-gram model",
+            ));
+        }
+
+        let n = take_u32(&mut bytes)? as usize;
+        let mut letters = Vec::with_capacity(2);
+        for _ in 0..2 {
+            let length = take_u32(&mut bytes)? as usize;
+            let text = std::str::from_utf8(take(&mut bytes, length)?).map_err(invalid_data)?;
+            letters.push(text);
+        }
+        let alphabet = Alphabet::new(letters[0], letters[1]).map_err(invalid_data)?;
+
+        table_size(n, &alphabet).map_err(invalid_data)?;
+
+        let mut counts = Vec::with_capacity(n);
+        let mut letter_count = 0;
+        for length in 1..=n {
+            let entries = take_u32(&mut bytes)? as usize;
+            if bytes.len() < 8 * entries {
+                return Err(invalid_data("The n-gram model is truncated"));
+            }
+
+            let mut seen = Vec::with_capacity(entries);
+            for _ in 0..entries {
+                seen.push((take_u32(&mut bytes)?, take_u32(&mut bytes)?));
+            }
+
+            let size = alphabet.len().pow(length as u32);
+            let total = seen.iter().map(|(_, count)| *count as u64).sum::<u64>();
+            if length == 1 {
+                letter_count = total;
+            }
+
+            let sorted = seen.windows(2).all(|pair| pair[0].0 < pair[1].0);
+            let in_table = seen
+                .iter()
+                .all(|(index, count)| (*index as usize) < size && *count > 0);
+            let expected = letter_count.checked_sub(length as u64 - 1);
+            if letter_count < n as u64 || Some(total) != expected || !sorted || !in_table {
+                return Err(invalid_data("The n-gram counts do not match the alphabet"));
+            }
+
+            counts.push(seen);
+        }
+
+        if !bytes.is_empty() {
+            return Err(invalid_data("The n-gram model has trailing data"));
+        }
+
+        Ok(NgramModel::from_counts(n, alphabet, counts))
+    }
+
+    /// Returns the english bigram model, it is trained once on the first call.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    ///
+    /// let model = NgramModel::english_bigrams();
+    /// assert!(model.score("Attack at dawn") > model.score("Atcakt a tdnaw"));
+    /// ```
+    pub fn english_bigrams() -> &'static NgramModel {
+        static BIGRAMS: OnceLock<NgramModel> = OnceLock::new();
+        BIGRAMS.get_or_init(|| NgramModel::english(2))
+    }
+
+    /// Returns the english quadgram model, it is trained once on the first call.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    ///
+    /// let model = NgramModel::english_quadgrams();
+    /// assert!(model.score("the quick brown fox") > model.score("eht kciuq nworb xof"));
+    /// ```
+    pub fn english_quadgrams() -> &'static NgramModel {
+        static QUADGRAMS: OnceLock<NgramModel> = OnceLock::new();
+        QUADGRAMS.get_or_init(|| NgramModel::english(4))
+    }
+
+    /// Returns the length of the n-grams of the model.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the alphabet of the model.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Returns how much a text looks like the corpus, as the average log-probability
+    /// of the n-grams of its letters. The higher the better.
+    ///
+    /// The score of a text shorter than the n-grams is negative infinity.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    ///
+    /// let model = NgramModel::english_bigrams();
+    /// assert!(model.score("Hello") > model.score("Jqqmx"));
+    /// assert_eq!(f64::NEG_INFINITY, model.score("H"));
+    /// ```
+    pub fn score(&self, text: &str) -> f64 {
+        self.score_letters(&letters(text, &self.alphabet).collect::<Vec<usize>>())
+    }
+
+    /// Returns the score of a text from the positions of its letters in the alphabet.
+    pub(crate) fn score_letters(&self, letters: &[usize]) -> f64 {
+        let windows = letters.windows(self.n);
+        let count = windows.len();
+
+        match count {
+            0 => f64::NEG_INFINITY,
+            _ => {
+                windows
+                    .map(|window| self.log_probabilities[table_index(window, self.alphabet.len())])
+                    .sum::<f64>()
+                    / count as f64
+            }
+        }
+    }
+
+    /// Builds the model from the counts of the n-grams of every length up to n.
+    fn from_counts(n: usize, alphabet: Alphabet, counts: Vec<Vec<(u32, u32)>>) -> NgramModel {
+        let base = alphabet.len();
+        let letter_count = counts[0]
+            .iter()
+            .map(|(_, count)| *count as u64)
+            .sum::<u64>();
+
+        let mut log_probabilities = Vec::new();
+        for (length, seen) in (1..=n).zip(counts.iter()) {
+            let total = letter_count - (length as u64 - 1);
+            log_probabilities = backed_off(seen, total, base, &log_probabilities);
+        }
+
+        NgramModel {
+            n,
+            alphabet,
+            counts,
+            log_probabilities,
+        }
+    }
+
+    pub(crate) fn english(n: usize) -> NgramModel {
+        NgramModel::train(ENGLISH_CORPUS, n, Alphabet::latin())
+            .expect("The english corpus contains every model")
+    }
+}
+
+/// Returns the table index and count of the n-grams of the letters that appear at least once.
+fn count(letters: &[usize], n: usize, base: usize) -> Vec<(u32, u32)> {
+    let mut counts = vec![0u32; base.pow(n as u32)];
+    for window in letters.windows(n) {
+        counts[table_index(window, base)] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, count)| *count > 0)
+        .map(|(index, count)| (index as u32, count))
+        .collect()
+}
+
+/// Returns the log-probabilities of the n-grams from the counts of the seen ones, the n-grams
+/// that never appear get the floor probability lowered by how rare their two (n-1)-grams are,
+/// so that the models still tell the unseen n-grams apart.
+fn backed_off(seen: &[(u32, u32)], total: u64, base: usize, shorter: &[f64]) -> Vec<f64> {
+    let total = total as f64;
+    let floor = (0.01 / total).log10();
+    let most_likely = shorter.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    let mut log_probabilities = match shorter.is_empty() {
+        true => vec![floor; base],
+        false => (0..base * shorter.len())
+            .map(|index| {
+                let (prefix, suffix) = (index / base, index % shorter.len());
+                floor + (shorter[prefix] + shorter[suffix]) / 2.0 - most_likely
+            })
+            .collect(),
+    };
+
+    for (index, count) in seen.iter() {
+        log_probabilities[*index as usize] = (*count as f64 / total).log10();
+    }
+
+    log_probabilities
+}
+
+/// Returns the number of n-grams of the alphabet.
+fn table_size(n: usize, alphabet: &Alphabet) -> Result<usize, Error> {
+    if n == 0 {
+        return Err(Error::InvalidKey("The n-gram length cannot be zero"));
+    }
+
+    (0..n)
+        .try_fold(1usize, |size, _| size.checked_mul(alphabet.len()))
+        .filter(|size| *size <= MAX_TABLE_SIZE)
+        .ok_or(Error::InvalidKey("The n-gram table is too large"))
+}
+
+/// Splits the first bytes off the data.
+fn take<'a>(bytes: &mut &'a [u8], count: usize) -> io::Result<&'a [u8]> {
+    if bytes.len() < count {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "The n-gram model is truncated",
+        ));
+    }
+
+    let (head, tail) = bytes.split_at(count);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn take_u32(bytes: &mut &[u8]) -> io::Result<u32> {
+    let head = take(bytes, 4)?;
+    Ok(u32::from_le_bytes(head.try_into().expect("Took 4 bytes")))
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(ErrorKind::InvalidData, error)
+}
+
+fn table_index(ngram: &[usize], base: usize) -> usize {
+    ngram.iter().fold(0, |index, letter| index * base + letter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NgramModel;
+    use crate::common::Alphabet;
+
+    #[test]
+    fn train_errors() {
+        assert!(NgramModel::train("abc", 0, Alphabet::latin()).is_err());
+        assert!(NgramModel::train("abc", 4, Alphabet::latin()).is_err());
+        assert!(NgramModel::train("abc", 8, Alphabet::latin()).is_err());
+    }
+
+    #[test]
+    fn floor_below_seen_ngrams() {
+        let model = NgramModel::train("abab", 2, Alphabet::latin()).unwrap();
+
+        assert!(model.score("ab") > model.score("ba"));
+        assert!(model.score("ba") > model.score("zz"));
+        assert_eq!(model.score("AB"), model.score("a, b"));
+    }
+
+    #[test]
+    fn english_models() {
+        let bigrams = NgramModel::english_bigrams();
+        let quadgrams = NgramModel::english_quadgrams();
+
+        assert_eq!(2, bigrams.n());
+        assert_eq!(4, quadgrams.n());
+        assert!(quadgrams.score("the quick brown fox") > quadgrams.score("eht kciuq nworb xof"));
+        assert!(std::ptr::eq(quadgrams, NgramModel::english_quadgrams()));
+    }
+
+    #[test]
+    fn save_load_round_trip() {
+        let model = NgramModel::train("Привет, мир", 2, Alphabet::cyrillic()).unwrap();
+        let mut bytes = Vec::new();
+        model.save(&mut bytes).unwrap();
+
+        let loaded = NgramModel::load(bytes.as_slice()).unwrap();
+        assert_eq!(2, loaded.n());
+        assert_eq!(model.alphabet(), loaded.alphabet());
+        assert_eq!(model.log_probabilities, loaded.log_probabilities);
+    }
+
+    #[test]
+    fn load_errors() {
+        let mut bytes = Vec::new();
+        NgramModel::train("abab", 2, Alphabet::latin())
+            .unwrap()
+            .save(&mut bytes)
+            .unwrap();
+
+        assert!(NgramModel::load(&bytes[..bytes.len() - 1]).is_err());
+        assert!(NgramModel::load(&bytes[1..]).is_err());
+
+        bytes.push(0);
+        assert!(NgramModel::load(bytes.as_slice()).is_err());
+    }
+
+    #[test]
+    fn train_file_errors() {
+        let path = std::env::temp_dir().join("cienli_train_file_errors.txt");
+        std::fs::write(&path, "ab").unwrap();
+
+        let short = NgramModel::train_file(&path, 4, Alphabet::latin());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(std::io::ErrorKind::InvalidData, short.unwrap_err().kind());
+        assert!(NgramModel::train_file(&path, 2, Alphabet::latin()).is_err());
+    }
+}
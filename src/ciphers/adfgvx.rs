@@ -0,0 +1,219 @@
+use crate::ciphers::columnar::Columnar;
+use crate::ciphers::polybius_square::PolybiusSquare;
+use crate::ciphers::Cipher;
+use crate::common::{KeySquare, LetterMerge};
+use crate::Error;
+
+/// ADFGX and ADFGVX Ciphers
+///
+/// The message is fractionated by a keyed polybius square labeled ADFGX (5x5) or
+/// ADFGVX (6x6), then the labels are transposed by columns in the order of the
+/// letters of the transposition key.
+///
+/// The struct is generated through the new() function or the adfgx() function.
+///
+pub struct Adfgvx {
+    polybius: PolybiusSquare,
+    labels: Vec<char>,
+    transposition: Columnar,
+}
+
+impl Adfgvx {
+    /// Initialize an ADFGVX cipher with the keyword of its 6x6 alphanumeric square
+    /// and the transposition key.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::adfgvx::Adfgvx;
+    /// let adfgvx = Adfgvx::new("na1c3h8tb2ome5wrpd4f6g7i9j0klqsuvxyz", "Privacy");
+    ///
+    /// assert!(adfgvx.is_ok());
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters, digits and spaces, and the
+    /// transposition key must not be empty.
+    pub fn new(keyword: &str, key: &str) -> Result<Adfgvx, Error> {
+        Adfgvx::with_square(KeySquare::alphanumeric(keyword)?, key)
+    }
+
+    /// Initialize an ADFGX cipher with the keyword of its 5x5 square ('J' merged with 'I')
+    /// and the transposition key.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::adfgvx::Adfgvx;
+    /// let adfgx = Adfgvx::adfgx("btalpdhozkqfvsngicuxmrewy", "Cargo");
+    ///
+    /// assert!(adfgx.is_ok());
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters and spaces, and the transposition key
+    /// must not be empty.
+    pub fn adfgx(keyword: &str, key: &str) -> Result<Adfgvx, Error> {
+        Adfgvx::with_square(KeySquare::new(keyword, LetterMerge::IJ)?, key)
+    }
+
+    /// Initialize the cipher with a square and the transposition key, a 5x5 square is
+    /// labeled ADFGX and a 6x6 square is labeled ADFGVX.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::adfgvx::Adfgvx;
+    /// use cienli::common::{KeySquare, LetterMerge};
+    /// let square = KeySquare::new("Wireless", LetterMerge::CK).unwrap();
+    /// let adfgx = Adfgvx::with_square(square, "Radio").unwrap();
+    ///
+    /// assert_eq!("RADIOSILENCE", adfgx.decipher(&adfgx.encipher("Radio silence")).unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The transposition key must not be empty.
+    pub fn with_square(square: KeySquare, key: &str) -> Result<Adfgvx, Error> {
+        let labels = match square.size() {
+            5 => "ADFGX",
+            _ => "ADFGVX",
+        };
+
+        Ok(Adfgvx {
+            transposition: Columnar::new(key)?,
+            polybius: PolybiusSquare::with_square(square, labels, false)?,
+            labels: labels.chars().collect(),
+        })
+    }
+
+    /// Enciphers a message with the ADFGX or ADFGVX cipher, the characters outside
+    /// of the square are removed.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::adfgvx::Adfgvx;
+    /// let adfgvx = Adfgvx::new("na1c3h8tb2ome5wrpd4f6g7i9j0klqsuvxyz", "Privacy").unwrap();
+    ///
+    /// assert_eq!("DGDDDAGDDGAFADDFDADVDVFAADVX", adfgvx.encipher("Attack at 1200AM"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        self.transposition
+            .encipher(&self.polybius.encipher(message))
+    }
+
+    /// Deciphers a cipher with the ADFGX or ADFGVX cipher, the whitespaces are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::adfgvx::Adfgvx;
+    /// let adfgx = Adfgvx::adfgx("btalpdhozkqfvsngicuxmrewy", "Cargo").unwrap();
+    ///
+    /// assert_eq!("ATTACKATONCE", adfgx.decipher("FAXDF ADDDG DGFFF AFAXA FAFX").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher must be made of an even number of labels.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        let mut labels = String::new();
+        for (position, character) in cipher.chars().enumerate() {
+            match character {
+                _ if character.is_whitespace() => {}
+                _ if self.labels.contains(&character) => labels.push(character),
+                _ => return Err(Error::MalformedCiphertext { position }),
+            }
+        }
+
+        if labels.len() % 2 != 0 {
+            return Err(Error::MalformedCiphertext {
+                position: cipher.chars().count(),
+            });
+        }
+
+        self.polybius
+            .decipher(&self.transposition.decipher(&labels))
+    }
+}
+
+impl Cipher for Adfgvx {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Adfgvx::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Adfgvx::decipher(self, cipher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Adfgvx;
+    use crate::Error;
+
+    const SQUARE: &str = "na1c3h8tb2ome5wrpd4f6g7i9j0klqsuvxyz";
+
+    #[test]
+    fn adfgvx_encipher_test() {
+        let adfgvx = Adfgvx::new(SQUARE, "PRIVACY").unwrap();
+
+        assert_eq!(
+            "DGDDDAGDDGAFADDFDADVDVFAADVX",
+            adfgvx.encipher("ATTACK AT 1200AM")
+        );
+    }
+
+    #[test]
+    fn adfgvx_decipher_test() {
+        let adfgvx = Adfgvx::new(SQUARE, "PRIVACY").unwrap();
+
+        assert_eq!(
+            "ATTACKAT1200AM",
+            adfgvx.decipher("DGDDDAGDDGAFADDFDADVDVFAADVX").unwrap()
+        );
+    }
+
+    #[test]
+    fn adfgx_test() {
+        let adfgx = Adfgvx::adfgx("btalpdhozkqfvsngicuxmrewy", "CARGO").unwrap();
+
+        assert_eq!("FAXDFADDDGDGFFFAFAXAFAFX", adfgx.encipher("Attack at once"));
+        assert_eq!(
+            "ATTACKATONCE",
+            adfgx.decipher("FAXDFADDDGDGFFFAFAXAFAFX").unwrap()
+        );
+    }
+
+    #[test]
+    fn incomplete_rows_test() {
+        let adfgvx = Adfgvx::new("Keyword 2024", "Transposition").unwrap();
+
+        for message in ["A", "SEVEN", "EXACTLYTHIRTEENLETTERS", "THE BRIDGE AT 0600"] {
+            let expected = message.replace(' ', "");
+            assert_eq!(
+                expected,
+                adfgvx.decipher(&adfgvx.encipher(message)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_key_letters_test() {
+        let adfgx = Adfgvx::adfgx("", "BALLOON").unwrap();
+
+        assert_eq!(
+            "RENDEZVOUS",
+            adfgx.decipher(&adfgx.encipher("Rendezvous")).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_test() {
+        let adfgx = Adfgvx::adfgx("", "KEY").unwrap();
+
+        assert_eq!(Some(Error::EmptyKey), Adfgvx::new("", "").err());
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 1 }),
+            adfgx.decipher("AV")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 3 }),
+            adfgx.decipher("ADF")
+        );
+    }
+}
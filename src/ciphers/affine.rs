@@ -1,3 +1,11 @@
+#[cfg(feature = "analysis")]
+use crate::analysis::{english_score, rank, Candidate};
+#[cfg(feature = "substitution")]
+use crate::ciphers::substitution::Substitution;
+use crate::ciphers::Cipher;
+use crate::common::Alphabet;
+use crate::stream::{transform_chars, Direction, StreamCipher};
+use crate::Error;
 use num_integer::Integer;
 
 /// Affine Cipher
@@ -7,6 +15,7 @@ use num_integer::Integer;
 pub struct Affine {
     alpha: u16,
     beta: u16,
+    alphabet: Alphabet,
 }
 
 impl Affine {
@@ -26,7 +35,6 @@ impl Affine {
     /// let affine = Affine::new((10, 2));
     /// assert!(affine.is_err());
     /// ```
-    /// this example will
     ///
     /// - Initialization with a big key:
     /// ```
@@ -34,16 +42,35 @@ impl Affine {
     /// let affine = Affine::new((27, 2));
     /// assert!(affine.is_err());
     /// ```
-    pub fn new(key: (u16, u16)) -> Result<Affine, &'static str> {
-        let is_key_valid = Affine::key_checker(key);
+    ///
+    /// # Errors:
+    /// The alpha must be in range 1 and 26 and co-prime with 26, the beta must not be greater than 26.
+    pub fn new(key: (u16, u16)) -> Result<Affine, Error> {
+        Affine::with_alphabet(key, Alphabet::latin())
+    }
 
-        match is_key_valid {
-            Ok(_v) => Ok(Affine {
-                alpha: key.0,
-                beta: key.1,
-            }),
-            Err(v) => Err(v),
-        }
+    /// Initialize a affine cipher with a key over a custom alphabet.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::affine::Affine;
+    /// use cienli::common::Alphabet;
+    /// let affine = Affine::with_alphabet((5, 2), Alphabet::greek()).unwrap();
+    ///
+    /// assert_eq!("Νψτγ :)", affine.encipher("Γεια :)"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The alpha must be in range 1 and the length of the alphabet and co-prime with it,
+    /// the beta must not be greater than the length of the alphabet.
+    pub fn with_alphabet(key: (u16, u16), alphabet: Alphabet) -> Result<Affine, Error> {
+        Affine::key_checker(key, alphabet.len())?;
+
+        Ok(Affine {
+            alpha: key.0,
+            beta: key.1,
+            alphabet,
+        })
     }
 
     /// Enciphers a message with the affine cipher.
@@ -58,15 +85,7 @@ impl Affine {
     pub fn encipher(&self, message: &str) -> String {
         message
             .chars()
-            .map(|character| match character {
-                'a'..='z' => {
-                    (((character as u16 - 97) * self.alpha + self.beta) % 26 + 97) as u8 as char
-                }
-                'A'..='Z' => {
-                    (((character as u16 - 65) * self.alpha + self.beta) % 26 + 65) as u8 as char
-                }
-                _ => character,
-            })
+            .map(|character| self.encipher_letter(character))
             .collect()
     }
 
@@ -80,34 +99,198 @@ impl Affine {
     /// assert_eq!("Hello :)", affine.decipher("Lwffu :)"));
     /// ```
     pub fn decipher(&self, message: &str) -> String {
-        let mut alpha_inv = 0;
-        while (self.alpha * alpha_inv) % 26 != 1 {
-            alpha_inv += 1;
-        }
+        let alpha_inv = self.alpha_inverse();
 
         message
             .chars()
-            .map(|character| match character {
-                'a'..='z' => {
-                    (alpha_inv * ((character as u16 - 97) - self.beta) % 26 + 97) as u8 as char
-                }
-                'A'..='Z' => {
-                    (alpha_inv * ((character as u16 - 65) - self.beta) % 26 + 65) as u8 as char
+            .map(|character| self.decipher_letter(character, alpha_inv))
+            .collect()
+    }
+
+    /// Tries the 312 valid keys over the latin alphabet and returns the candidates
+    /// ranked by how close the plaintexts are to english.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::affine::Affine;
+    /// let affine = Affine::new((7, 11)).unwrap();
+    /// let cipher = affine.encipher("Meet me at the old bridge after midnight.");
+    ///
+    /// let candidates = Affine::crack(&cipher);
+    /// assert_eq!(312, candidates.len());
+    /// assert_eq!((7, 11), candidates[0].key);
+    /// assert_eq!("Meet me at the old bridge after midnight.", candidates[0].plaintext);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack(cipher: &str) -> Vec<Candidate<(u16, u16)>> {
+        let mut candidates = (1..26)
+            .flat_map(|alpha| (0..26).map(move |beta| (alpha, beta)))
+            .filter_map(|key| Affine::new(key).ok())
+            .map(|affine| {
+                let plaintext = affine.decipher(cipher);
+                Candidate {
+                    key: (affine.alpha, affine.beta),
+                    score: english_score(&plaintext),
+                    plaintext,
                 }
-                _ => character,
             })
-            .collect()
+            .collect::<Vec<Candidate<(u16, u16)>>>();
+
+        rank(&mut candidates);
+        candidates
+    }
+
+    /// Derives the key over the latin alphabet from a known plaintext and its cipher.
+    ///
+    /// The letters of both texts are paired in order, two pairs whose plaintext letters
+    /// differ by a number co-prime with 26 are enough to solve the key.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::affine::Affine;
+    ///
+    /// assert_eq!((5, 2), Affine::solve_known_plaintext("Hello", "Lwffu").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The letters must contain two usable pairs and every pair must agree with the derived key.
+    pub fn solve_known_plaintext(plain: &str, cipher: &str) -> Result<(u16, u16), Error> {
+        let alphabet = Alphabet::latin();
+        let index = |character| alphabet.index_of(character).map(|(index, _)| index);
+
+        let pairs = plain
+            .chars()
+            .filter_map(index)
+            .zip(cipher.chars().filter_map(index))
+            .collect::<Vec<(usize, usize)>>();
+
+        let key = pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(position, first)| {
+                pairs[position + 1..]
+                    .iter()
+                    .map(move |second| (first, second))
+            })
+            .find_map(|(&(p1, c1), &(p2, c2))| {
+                let inverse = modular_inverse((26 + p1 - p2) % 26, 26)?;
+                let alpha = (26 + c1 - c2) * inverse % 26;
+                let beta = (26 + c1 - alpha * p1 % 26) % 26;
+                Some((alpha as u16, beta as u16))
+            })
+            .ok_or(Error::InvalidKey(
+                "The known plaintext does not contain enough letters to solve the key",
+            ))?;
+
+        let affine = Affine::new(key)?;
+        if pairs.iter().all(|&(plain, cipher)| {
+            (plain * affine.alpha as usize + affine.beta as usize) % 26 == cipher
+        }) {
+            Ok(key)
+        } else {
+            Err(Error::InvalidKey(
+                "The known plaintext and cipher do not match any key",
+            ))
+        }
+    }
+
+    fn encipher_letter(&self, character: char) -> char {
+        match self.alphabet.index_of(character) {
+            Some((index, case)) => self
+                .alphabet
+                .letter(index * self.alpha as usize + self.beta as usize, case),
+            None => character,
+        }
+    }
+
+    fn decipher_letter(&self, character: char, alpha_inv: usize) -> char {
+        match self.alphabet.index_of(character) {
+            Some((index, case)) => self.alphabet.letter(
+                alpha_inv * (self.alphabet.len() + index - self.beta as usize),
+                case,
+            ),
+            None => character,
+        }
     }
 
-    fn key_checker(key: (u16, u16)) -> Result<(), &'static str> {
-        if (key.0 >= 1 && key.0 <= 26) && key.1 <= 26 {
-            if key.0.gcd(&26) == 1 {
+    /// Returns the substitution cipher equivalent to the affine cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::affine::Affine;
+    /// let substitution = Affine::new((5, 8)).unwrap().to_substitution();
+    ///
+    /// assert_eq!("INSXCHMRWBGLQVAFKPUZEJOTYD", substitution.cipher_alphabet());
+    /// ```
+    #[cfg(feature = "substitution")]
+    pub fn to_substitution(&self) -> Substitution {
+        let length = self.alphabet.len();
+
+        Substitution::from_mapping(
+            (0..length)
+                .map(|index| (self.alpha as usize * index + self.beta as usize) % length)
+                .collect(),
+            self.alphabet.clone(),
+        )
+        .expect("The alpha of an affine cipher is co-prime with the length of the alphabet")
+    }
+
+    fn alpha_inverse(&self) -> usize {
+        modular_inverse(self.alpha as usize, self.alphabet.len()).unwrap_or_default()
+    }
+
+    fn key_checker(key: (u16, u16), modulus: usize) -> Result<(), Error> {
+        let (alpha, beta) = (key.0 as usize, key.1 as usize);
+
+        if (alpha >= 1 && alpha <= modulus) && beta <= modulus {
+            if alpha.gcd(&modulus) == 1 {
                 Ok(())
             } else {
-                Err("The alpha is not co-prime with 26")
+                Err(Error::KeyNotCoprime {
+                    key: alpha,
+                    modulus,
+                })
             }
         } else {
-            Err("The is greater than 26")
+            Err(Error::InvalidKey(
+                "Alpha and beta must not be greater than the length of the alphabet",
+            ))
+        }
+    }
+}
+
+fn modular_inverse(value: usize, modulus: usize) -> Option<usize> {
+    (0..modulus).find(|inverse| (value * inverse) % modulus == 1 % modulus)
+}
+
+impl Cipher for Affine {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Affine::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Affine::decipher(self, cipher))
+    }
+}
+
+impl StreamCipher for Affine {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        _position: &mut usize,
+        direction: Direction,
+    ) -> usize {
+        match direction {
+            Direction::Encipher => {
+                transform_chars(input, output, |character| self.encipher_letter(character))
+            }
+            Direction::Decipher => {
+                let alpha_inv = self.alpha_inverse();
+                transform_chars(input, output, |character| {
+                    self.decipher_letter(character, alpha_inv)
+                })
+            }
         }
     }
 }
@@ -115,6 +298,11 @@ impl Affine {
 #[cfg(test)]
 mod tests {
     use super::Affine;
+    use crate::common::Alphabet;
+
+    use crate::stream::{CipherReader, Direction};
+    use crate::Error;
+    use std::io::Read;
 
     #[test]
     fn invalid_key_length_test() {
@@ -123,7 +311,13 @@ mod tests {
 
     #[test]
     fn invalid_key_coprime_test() {
-        assert!(Affine::new((10, 2)).is_err())
+        assert_eq!(
+            Some(Error::KeyNotCoprime {
+                key: 10,
+                modulus: 26
+            }),
+            Affine::new((10, 2)).err()
+        )
     }
 
     #[test]
@@ -137,4 +331,74 @@ mod tests {
         let affine = Affine::new((5, 2)).unwrap();
         assert_eq!("Hello :)", affine.decipher("Lwffu :)"))
     }
+
+    #[test]
+    fn custom_alphabet_test() {
+        let affine = Affine::with_alphabet((7, 3), Alphabet::alphanumeric()).unwrap();
+
+        assert_eq!(
+            "LETTERS 0189",
+            affine.decipher(&affine.encipher("LETTERS 0189"))
+        );
+        assert!(Affine::with_alphabet((6, 3), Alphabet::alphanumeric()).is_err());
+    }
+
+    #[test]
+    fn stream_decipher_test() {
+        let affine = Affine::new((5, 2)).unwrap();
+        let mut reader = CipherReader::new("Lwffu :)".as_bytes(), affine, Direction::Decipher);
+
+        let mut message = String::new();
+        reader.read_to_string(&mut message).unwrap();
+        assert_eq!("Hello :)", message);
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_test() {
+        let affine = Affine::new((11, 20)).unwrap();
+        let cipher = affine.encipher("Attack the northern gate at first light, bring the ladders.");
+        let candidates = Affine::crack(&cipher);
+
+        assert_eq!((11, 20), candidates[0].key);
+        assert_eq!(
+            "Attack the northern gate at first light, bring the ladders.",
+            candidates[0].plaintext
+        );
+    }
+
+    #[test]
+    fn known_plaintext_test() {
+        let affine = Affine::new((19, 4)).unwrap();
+        let cipher = affine.encipher("Known plaintext");
+
+        assert_eq!(
+            (19, 4),
+            Affine::solve_known_plaintext("Known plaintext", &cipher).unwrap()
+        );
+        assert!(Affine::solve_known_plaintext("aaaa", "bbbb").is_err());
+        assert!(Affine::solve_known_plaintext("abcd", "aaaa").is_err());
+    }
+
+    #[test]
+    fn decipher_small_letters_test() {
+        let affine = Affine::new((5, 8)).unwrap();
+        assert_eq!("Hello", affine.decipher(&affine.encipher("Hello")))
+    }
+
+    #[test]
+    #[cfg(feature = "substitution")]
+    fn to_substitution_test() {
+        let affine = Affine::with_alphabet((5, 3), Alphabet::greek()).unwrap();
+        let substitution = affine.to_substitution();
+
+        assert_eq!(
+            affine.encipher("Γεια σου"),
+            substitution.encipher("Γεια σου")
+        );
+        assert_eq!(
+            "Γεια σου",
+            substitution.decipher(&affine.encipher("Γεια σου"))
+        );
+    }
 }
@@ -1,29 +1,43 @@
+#[cfg(feature = "substitution")]
+use crate::ciphers::substitution::Substitution;
+use crate::ciphers::Cipher;
+use crate::common::Alphabet;
+use crate::stream::{transform_chars, Direction, StreamCipher};
+use crate::Error;
+
 /// Atbash Cipher
 ///
 /// The struct is generated through the new() function
 ///
-pub struct Atbash<'a> {
-    message: &'a str,
+pub struct Atbash {
+    alphabet: Alphabet,
 }
 
-impl Atbash<'_> {
-    /// Initialize a atbash cipher with a message or a cipher.
+impl Atbash {
+    /// Initialize a atbash cipher.
     ///
     /// # Examples:
-    /// - Initialization with a message:
     /// ```
     /// use cienli::ciphers::atbash::Atbash;
-    /// let atbash = Atbash::new("Hello Friend :)");
+    /// let atbash = Atbash::new();
     /// ```
     ///
-    /// - Initialization with a cipher:
+    pub fn new() -> Atbash {
+        Atbash::with_alphabet(Alphabet::latin())
+    }
+
+    /// Initialize a atbash cipher over a custom alphabet.
+    ///
+    /// # Examples:
     /// ```
     /// use cienli::ciphers::atbash::Atbash;
-    /// let atbash = Atbash::new("Svool Uirvmw :)");
-    /// ```
+    /// use cienli::common::Alphabet;
+    /// let atbash = Atbash::with_alphabet(Alphabet::hebrew());
     ///
-    pub fn new(message: &str) -> Atbash {
-        Atbash { message }
+    /// assert_eq!("תשר", atbash.encipher("אבג"));
+    /// ```
+    pub fn with_alphabet(alphabet: Alphabet) -> Atbash {
+        Atbash { alphabet }
     }
 
     /// Enciphers a message with the atbash cipher.
@@ -31,18 +45,14 @@ impl Atbash<'_> {
     /// # Example:
     /// ```
     /// use cienli::ciphers::atbash::Atbash;
-    /// let atbash = Atbash::new("Hello Friend :)");
+    /// let atbash = Atbash::new();
     ///
-    /// assert_eq!("Svool Uirvmw :)", atbash.encipher());
+    /// assert_eq!("Svool Uirvmw :)", atbash.encipher("Hello Friend :)"));
     /// ```
-    pub fn encipher(&self) -> String {
-        self.message
+    pub fn encipher(&self, message: &str) -> String {
+        message
             .chars()
-            .map(|character| match character {
-                'A'..='Z' => ((90 - character as u8) + 65) as char,
-                'a'..='z' => ((122 - character as u8) + 97) as char,
-                _ => character,
-            })
+            .map(|character| self.reverse_letter(character))
             .collect()
     }
 
@@ -51,28 +61,103 @@ impl Atbash<'_> {
     /// # Example:
     /// ```
     /// use cienli::ciphers::atbash::Atbash;
-    /// let atbash = Atbash::new("Svool Uirvmw :)");
+    /// let atbash = Atbash::new();
     ///
-    /// assert_eq!("Hello Friend :)", atbash.decipher());
+    /// assert_eq!("Hello Friend :)", atbash.decipher("Svool Uirvmw :)"));
     /// ```
-    pub fn decipher(&self) -> String {
-        self.encipher()
+    pub fn decipher(&self, cipher: &str) -> String {
+        self.encipher(cipher)
+    }
+
+    /// Returns the substitution cipher equivalent to the atbash cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::atbash::Atbash;
+    /// let substitution = Atbash::new().to_substitution();
+    ///
+    /// assert_eq!("ZYXWVUTSRQPONMLKJIHGFEDCBA", substitution.cipher_alphabet());
+    /// ```
+    #[cfg(feature = "substitution")]
+    pub fn to_substitution(&self) -> Substitution {
+        let length = self.alphabet.len();
+
+        Substitution::from_mapping(
+            (0..length).map(|index| length - 1 - index).collect(),
+            self.alphabet.clone(),
+        )
+        .expect("A reversed alphabet is a permutation of the alphabet")
+    }
+
+    fn reverse_letter(&self, character: char) -> char {
+        match self.alphabet.index_of(character) {
+            Some((index, case)) => self.alphabet.letter(self.alphabet.len() - 1 - index, case),
+            None => character,
+        }
+    }
+}
+
+impl Default for Atbash {
+    fn default() -> Self {
+        Atbash::new()
+    }
+}
+
+impl Cipher for Atbash {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Atbash::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Atbash::decipher(self, cipher))
+    }
+}
+
+impl StreamCipher for Atbash {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        _position: &mut usize,
+        _direction: Direction,
+    ) -> usize {
+        transform_chars(input, output, |character| self.reverse_letter(character))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Atbash;
+    use crate::common::Alphabet;
 
     #[test]
     fn atbash_encipher() {
-        let atbash = Atbash::new("Hello Friend :)");
-        assert_eq!("Svool Uirvmw :)", atbash.encipher())
+        let atbash = Atbash::new();
+        assert_eq!("Svool Uirvmw :)", atbash.encipher("Hello Friend :)"))
     }
 
     #[test]
     fn atbash_decipher() {
-        let atbash = Atbash::new("Svool Uirvmw :)");
-        assert_eq!("Hello Friend :)", atbash.decipher());
+        let atbash = Atbash::new();
+        assert_eq!("Hello Friend :)", atbash.decipher("Svool Uirvmw :)"));
+    }
+
+    #[test]
+    fn atbash_custom_alphabet() {
+        let atbash = Atbash::with_alphabet(Alphabet::german());
+
+        assert_eq!("Größe", atbash.decipher(&atbash.encipher("Größe")));
+        assert_eq!("ẞ", atbash.encipher("A"));
+    }
+
+    #[test]
+    #[cfg(feature = "substitution")]
+    fn to_substitution_test() {
+        let atbash = Atbash::with_alphabet(Alphabet::hebrew());
+
+        assert_eq!(
+            atbash.encipher("שלום"),
+            atbash.to_substitution().encipher("שלום")
+        );
     }
 }
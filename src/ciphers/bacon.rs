@@ -1,4 +1,5 @@
-use regex::{Captures, Regex};
+use crate::ciphers::Cipher;
+use crate::Error;
 
 /// Bacon Cipher
 ///
@@ -17,9 +18,14 @@ impl Bacon {
     ///
     /// let bacon = Bacon::new(('a', 'b')).unwrap();
     /// ```
-    pub fn new(letters: (char, char)) -> Result<Bacon, &'static str> {
+    ///
+    /// # Errors:
+    /// The letters must be different from each other.
+    pub fn new(letters: (char, char)) -> Result<Bacon, Error> {
         if letters.0 == letters.1 {
-            return Err("Error: Letters must be different from each other!!");
+            return Err(Error::InvalidKey(
+                "Letters must be different from each other",
+            ));
         }
         Ok(Bacon { letters })
     }
@@ -73,7 +79,7 @@ impl Bacon {
     ///
     /// assert_eq!(
     /// "HELLO FRIEND",
-    /// bacon.decipher("aabbbaabaaababbababbabbba aababbaaababaaaaabaaabbabaaabb")
+    /// bacon.decipher("aabbbaabaaababbababbabbba aababbaaababaaaaabaaabbabaaabb").unwrap()
     /// );
     /// ```
     ///
@@ -85,27 +91,76 @@ impl Bacon {
     ///
     /// assert_eq!(
     /// "HELLO FRIEND",
-    /// bacon.decipher("++===++=+++=+==+=+==+===+ ++=+==+++=+=+++++=+++==+=+++==")
+    /// bacon.decipher("++===++=+++=+==+=+==+===+ ++=+==+++=+=+++++=+++==+=+++==").unwrap()
     /// );
     /// ```
-    pub fn decipher(&self, message: &str) -> String {
-        let binary_message = message
-            .replace(&self.letters.0.to_string(), "0")
-            .replace(&self.letters.1.to_string(), "1");
+    ///
+    /// # Errors:
+    /// Every group of letters must be five letters long and represent a letter between 'A' and 'Z'.
+    pub fn decipher(&self, message: &str) -> Result<String, Error> {
+        let mut result = String::new();
+
+        let mut group: u8 = 0;
+        let mut group_len = 0;
+        let mut group_start = 0;
+
+        for (position, character) in message.chars().enumerate() {
+            let bit = if character == self.letters.0 {
+                0
+            } else if character == self.letters.1 {
+                1
+            } else {
+                if group_len != 0 {
+                    return Err(Error::MalformedCiphertext {
+                        position: group_start,
+                    });
+                }
+                result.push(character);
+                continue;
+            };
+
+            if group_len == 0 {
+                group_start = position;
+            }
+            group = (group << 1) | bit;
+            group_len += 1;
+
+            if group_len == 5 {
+                if group > 25 {
+                    return Err(Error::MalformedCiphertext {
+                        position: group_start,
+                    });
+                }
+                result.push((group + 65) as char);
+                group = 0;
+                group_len = 0;
+            }
+        }
 
-        let re = Regex::new(r"[01]{5}").unwrap();
+        if group_len != 0 {
+            return Err(Error::MalformedCiphertext {
+                position: group_start,
+            });
+        }
 
-        let result = re.replace_all(&binary_message, |cap: &Captures| {
-            ((u8::from_str_radix(&cap[0], 2).unwrap() + 65) as char).to_string()
-        });
+        Ok(result)
+    }
+}
+
+impl Cipher for Bacon {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Bacon::encipher(self, message))
+    }
 
-        result.to_string()
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Bacon::decipher(self, cipher)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Bacon;
+    use crate::Error;
 
     #[test]
     fn encipher_test() {
@@ -123,7 +178,9 @@ mod tests {
 
         assert_eq!(
             "HELLO FRIEND",
-            bacon.decipher("aabbbaabaaababbababbabbba aababbaaababaaaaabaaabbabaaabb")
+            bacon
+                .decipher("aabbbaabaaababbababbabbba aababbaaababaaaaabaaabbabaaabb")
+                .unwrap()
         );
     }
 
@@ -143,7 +200,23 @@ mod tests {
 
         assert_eq!(
             "HELLO FRIEND",
-            bacon.decipher("++===++=+++=+==+=+==+===+ ++=+==+++=+=+++++=+++==+=+++==")
+            bacon
+                .decipher("++===++=+++=+==+=+==+===+ ++=+==+++=+=+++++=+++==+=+++==")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn decipher_malformed_test() {
+        let bacon = Bacon::new(('a', 'b')).unwrap();
+
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 6 }),
+            bacon.decipher("aabbb aab")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 0 }),
+            bacon.decipher("bbbbb")
         );
     }
 
@@ -0,0 +1,208 @@
+use crate::ciphers::Cipher;
+use crate::common::{KeySquare, LetterMerge};
+use crate::Error;
+
+/// Bifid Cipher
+///
+/// The rows and the columns of the letters in a polybius square are written in two lines,
+/// then read in pairs to get the letters of the cipher.
+///
+/// The struct is generated through the new() function.
+///
+pub struct Bifid {
+    square: KeySquare,
+    period: Option<usize>,
+}
+
+impl Bifid {
+    /// Initialize a bifid cipher with the keyword of its 5x5 square ('J' merged with 'I'),
+    /// the whole message is fractionated at once.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid keyword:
+    /// ```
+    /// use cienli::ciphers::bifid::Bifid;
+    /// let bifid = Bifid::new("Delastelle");
+    ///
+    /// assert!(bifid.is_ok());
+    /// ```
+    ///
+    /// - Initialization with an invalid keyword:
+    /// ```
+    /// use cienli::ciphers::bifid::Bifid;
+    /// let bifid = Bifid::new("Delastelle 1901");
+    ///
+    /// assert!(bifid.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters and spaces.
+    pub fn new(keyword: &str) -> Result<Bifid, Error> {
+        Bifid::with_square(KeySquare::new(keyword, LetterMerge::IJ)?, None)
+    }
+
+    /// Initialize a bifid cipher with a square and the period, the number of letters
+    /// fractionated together. Without a period the whole message is fractionated at once.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::bifid::Bifid;
+    /// use cienli::common::{KeySquare, LetterMerge};
+    /// let square = KeySquare::new("Delastelle", LetterMerge::IJ).unwrap();
+    /// let bifid = Bifid::with_square(square, Some(5)).unwrap();
+    ///
+    /// assert_eq!("DEFENDTHEEASTWALL", bifid.decipher(&bifid.encipher("Defend the east wall")).unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The period must not be zero.
+    pub fn with_square(square: KeySquare, period: Option<usize>) -> Result<Bifid, Error> {
+        if period == Some(0) {
+            return Err(Error::InvalidKey("Period cannot be zero"));
+        }
+
+        Ok(Bifid { square, period })
+    }
+
+    /// Enciphers a message with the bifid cipher, the characters outside of the square
+    /// are removed.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::bifid::Bifid;
+    /// let bifid = Bifid::new("BGWKZQPNDSIOAXEFCLUMTHYVR").unwrap();
+    ///
+    /// assert_eq!("UAEOLWRINS", bifid.encipher("Flee at once"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let positions = message
+            .chars()
+            .filter_map(|character| self.square.position(character))
+            .collect::<Vec<(usize, usize)>>();
+
+        self.blocks(&positions)
+            .flat_map(|block| {
+                let coordinates = block
+                    .iter()
+                    .map(|(row, _)| *row)
+                    .chain(block.iter().map(|(_, column)| *column))
+                    .collect::<Vec<usize>>();
+
+                coordinates
+                    .chunks(2)
+                    .map(|pair| self.square.letter(pair[0], pair[1]))
+                    .collect::<Vec<char>>()
+            })
+            .collect()
+    }
+
+    /// Deciphers a cipher with the bifid cipher, the whitespaces are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::bifid::Bifid;
+    /// let bifid = Bifid::new("BGWKZQPNDSIOAXEFCLUMTHYVR").unwrap();
+    ///
+    /// assert_eq!("FLEEATONCE", bifid.decipher("UAEOL WRINS").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher must only contain letters of the square and whitespaces.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        let mut positions = Vec::new();
+        for character in cipher
+            .chars()
+            .filter(|character| !character.is_whitespace())
+        {
+            positions.push(
+                self.square
+                    .position(character)
+                    .ok_or(Error::UnsupportedCharacter(character))?,
+            );
+        }
+
+        Ok(self
+            .blocks(&positions)
+            .flat_map(|block| {
+                let coordinates = block
+                    .iter()
+                    .flat_map(|(row, column)| [*row, *column])
+                    .collect::<Vec<usize>>();
+                let (rows, columns) = coordinates.split_at(block.len());
+
+                rows.iter()
+                    .zip(columns)
+                    .map(|(row, column)| self.square.letter(*row, *column))
+                    .collect::<Vec<char>>()
+            })
+            .collect())
+    }
+
+    fn blocks<'a>(
+        &self,
+        positions: &'a [(usize, usize)],
+    ) -> impl Iterator<Item = &'a [(usize, usize)]> {
+        positions.chunks(self.period.unwrap_or(positions.len()).max(1))
+    }
+}
+
+impl Cipher for Bifid {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Bifid::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Bifid::decipher(self, cipher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bifid;
+    use crate::common::{KeySquare, LetterMerge};
+    use crate::Error;
+
+    const SQUARE: &str = "BGWKZQPNDSIOAXEFCLUMTHYVR";
+
+    #[test]
+    fn encipher_test() {
+        let bifid = Bifid::new(SQUARE).unwrap();
+        assert_eq!("UAEOLWRINS", bifid.encipher("FLEE AT ONCE"));
+    }
+
+    #[test]
+    fn decipher_test() {
+        let bifid = Bifid::new(SQUARE).unwrap();
+        assert_eq!("FLEEATONCE", bifid.decipher("UAEOLWRINS").unwrap());
+    }
+
+    #[test]
+    fn period_test() {
+        let square = KeySquare::new(SQUARE, LetterMerge::IJ).unwrap();
+        let bifid = Bifid::with_square(square, Some(3)).unwrap();
+        let cipher = bifid.encipher("Flee at once");
+
+        assert_ne!("UAEOLWRINS", cipher);
+        assert_eq!("FLEEATONCE", bifid.decipher(&cipher).unwrap());
+    }
+
+    #[test]
+    fn alphanumeric_square_test() {
+        let square = KeySquare::alphanumeric("Cipher 42").unwrap();
+        let bifid = Bifid::with_square(square, Some(7)).unwrap();
+
+        assert_eq!(
+            "MEETAT0930",
+            bifid.decipher(&bifid.encipher("Meet at 0930")).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_test() {
+        let square = KeySquare::new("", LetterMerge::IJ).unwrap();
+        let bifid = Bifid::new("").unwrap();
+
+        assert!(Bifid::with_square(square, Some(0)).is_err());
+        assert_eq!(Err(Error::UnsupportedCharacter('1')), bifid.decipher("AB1"));
+    }
+}
@@ -1,9 +1,19 @@
+#[cfg(feature = "analysis")]
+use crate::analysis::{english_score, rank, Candidate};
+#[cfg(feature = "substitution")]
+use crate::ciphers::substitution::Substitution;
+use crate::ciphers::Cipher;
+use crate::common::Alphabet;
+use crate::stream::{transform_chars, Direction, StreamCipher};
+use crate::Error;
+
 /// Caesar Cipher
 ///
 /// The struct is generated through the new() function.
 ///
 pub struct Caesar {
     rotation: u8,
+    alphabet: Alphabet,
 }
 
 impl Caesar {
@@ -17,11 +27,30 @@ impl Caesar {
     ///
     /// # Errors:
     /// The rotation must be in range 1 and 26.
-    pub fn new(rotation: u8) -> Result<Caesar, &'static str> {
-        if (1..=26).contains(&rotation) {
-            Ok(Caesar { rotation })
+    pub fn new(rotation: u8) -> Result<Caesar, Error> {
+        Caesar::with_alphabet(rotation, Alphabet::latin())
+    }
+
+    /// Initialize a caesar cipher with a rotation over a custom alphabet.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::caesar::Caesar;
+    /// use cienli::common::Alphabet;
+    /// let caesar = Caesar::with_alphabet(3, Alphabet::cyrillic()).unwrap();
+    ///
+    /// assert_eq!("Тулезх, жуцё!", caesar.encipher("Привет, друг!"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The rotation must be in range 1 and the length of the alphabet.
+    pub fn with_alphabet(rotation: u8, alphabet: Alphabet) -> Result<Caesar, Error> {
+        if (1..=alphabet.len()).contains(&(rotation as usize)) {
+            Ok(Caesar { rotation, alphabet })
         } else {
-            Err("Error: Rotation must be in range 1 and 26!!")
+            Err(Error::InvalidKey(
+                "Rotation must be in range 1 and the length of the alphabet",
+            ))
         }
     }
 
@@ -37,7 +66,7 @@ impl Caesar {
     /// assert_eq!("Mjqqt, Ymnx Nx F Yjxy", caesar.encipher("Hello, This Is A Test"));
     /// ```
     pub fn encipher(&self, message: &str) -> String {
-        Caesar::shift(message, self.rotation)
+        self.shift(message, self.rotation as usize)
     }
 
     /// Deciphers a cipher with the caesar cipher.
@@ -51,24 +80,131 @@ impl Caesar {
     /// assert_eq!("Hello, This Is A Test", caesar.decipher("Mjqqt, Ymnx Nx F Yjxy"));
     /// ```
     pub fn decipher(&self, message: &str) -> String {
-        Caesar::shift(message, 26 - self.rotation)
+        self.shift(message, self.alphabet.len() - self.rotation as usize)
+    }
+
+    /// Tries every rotation on an english ciphertext and returns the candidates
+    /// ranked from the most likely to the least likely.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::caesar::Caesar;
+    /// let candidates = Caesar::crack("Mjqqt, Ymnx Nx F Yjxy");
+    ///
+    /// assert_eq!(5, candidates[0].key);
+    /// assert_eq!("Hello, This Is A Test", candidates[0].plaintext);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack(cipher: &str) -> Vec<Candidate<u8>> {
+        Caesar::crack_with(cipher, Alphabet::latin(), english_score)
+    }
+
+    /// Tries every rotation over a custom alphabet and returns the candidates ranked by a
+    /// scoring function, the higher the score the more likely the plaintext.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::{chi_squared, ENGLISH_FREQUENCIES};
+    /// use cienli::ciphers::caesar::Caesar;
+    /// use cienli::common::Alphabet;
+    ///
+    /// let candidates = Caesar::crack_with("Wkh vhfuhw lv lq wkh jdughq", Alphabet::latin(), |text| {
+    ///     -chi_squared(text, &Alphabet::latin(), &ENGLISH_FREQUENCIES)
+    /// });
+    ///
+    /// assert_eq!("The secret is in the garden", candidates[0].plaintext);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack_with<F>(cipher: &str, alphabet: Alphabet, score: F) -> Vec<Candidate<u8>>
+    where
+        F: Fn(&str) -> f64,
+    {
+        let mut candidates = (1..=alphabet.len().min(u8::MAX as usize) as u8)
+            .filter_map(|rotation| Caesar::with_alphabet(rotation, alphabet.clone()).ok())
+            .map(|caesar| {
+                let plaintext = caesar.decipher(cipher);
+                Candidate {
+                    key: caesar.rotation,
+                    score: score(&plaintext),
+                    plaintext,
+                }
+            })
+            .collect::<Vec<Candidate<u8>>>();
+
+        rank(&mut candidates);
+        candidates
+    }
+
+    /// Returns the substitution cipher equivalent to the caesar cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::caesar::Caesar;
+    /// let substitution = Caesar::new(3).unwrap().to_substitution();
+    ///
+    /// assert_eq!("DEFGHIJKLMNOPQRSTUVWXYZABC", substitution.cipher_alphabet());
+    /// ```
+    #[cfg(feature = "substitution")]
+    pub fn to_substitution(&self) -> Substitution {
+        let length = self.alphabet.len();
+
+        Substitution::from_mapping(
+            (0..length)
+                .map(|index| (index + self.rotation as usize) % length)
+                .collect(),
+            self.alphabet.clone(),
+        )
+        .expect("A rotation is a permutation of the alphabet")
     }
 
-    fn shift(message: &str, rotation: u8) -> String {
+    fn shift(&self, message: &str, rotation: usize) -> String {
         message
             .chars()
-            .map(|character| match character {
-                'A'..='Z' => (((character as u8 - 65 + rotation) % 26) + 65) as char,
-                'a'..='z' => (((character as u8 - 97 + rotation) % 26) + 97) as char,
-                _ => character,
-            })
+            .map(|character| self.shift_letter(character, rotation))
             .collect()
     }
+
+    fn shift_letter(&self, character: char, rotation: usize) -> char {
+        match self.alphabet.index_of(character) {
+            Some((index, case)) => self.alphabet.letter(index + rotation, case),
+            None => character,
+        }
+    }
+}
+
+impl Cipher for Caesar {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Caesar::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Caesar::decipher(self, cipher))
+    }
+}
+
+impl StreamCipher for Caesar {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        _position: &mut usize,
+        direction: Direction,
+    ) -> usize {
+        let rotation = match direction {
+            Direction::Encipher => self.rotation as usize,
+            Direction::Decipher => self.alphabet.len() - self.rotation as usize,
+        };
+
+        transform_chars(input, output, |character| {
+            self.shift_letter(character, rotation)
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Caesar;
+    use crate::common::Alphabet;
 
     #[test]
     fn encipher_test() {
@@ -90,6 +226,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_alphabet_test() {
+        let caesar = Caesar::with_alphabet(10, Alphabet::alphanumeric()).unwrap();
+
+        assert_eq!("ROVVY, EC", caesar.encipher("HELLO, 42"));
+        assert_eq!("HELLO, 42", caesar.decipher("ROVVY, EC"));
+    }
+
+    #[test]
+    fn custom_alphabet_rotation() {
+        assert!(Caesar::with_alphabet(33, Alphabet::cyrillic()).is_ok());
+        assert!(Caesar::with_alphabet(34, Alphabet::cyrillic()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_test() {
+        let caesar = Caesar::new(17).unwrap();
+        let cipher =
+            caesar.encipher("Veni, vidi, vici. The die is cast, and the legions cross the river.");
+        let candidates = Caesar::crack(&cipher);
+
+        assert_eq!(26, candidates.len());
+        assert_eq!(17, candidates[0].key);
+        assert_eq!(
+            "Veni, vidi, vici. The die is cast, and the legions cross the river.",
+            candidates[0].plaintext
+        );
+        assert!(candidates[0].score > candidates[1].score);
+    }
+
     #[test]
     fn big_rotation() {
         assert!(Caesar::new(34).is_err());
@@ -99,4 +266,16 @@ mod tests {
     fn small_rotation() {
         assert!(Caesar::new(0).is_err());
     }
+
+    #[test]
+    #[cfg(feature = "substitution")]
+    fn to_substitution_test() {
+        let caesar = Caesar::with_alphabet(7, Alphabet::cyrillic()).unwrap();
+        let substitution = caesar.to_substitution();
+
+        assert_eq!(
+            caesar.encipher("Привет, мир!"),
+            substitution.encipher("Привет, мир!")
+        );
+    }
 }
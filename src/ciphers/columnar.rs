@@ -0,0 +1,292 @@
+use crate::ciphers::Cipher;
+use crate::Error;
+
+/// Columnar Transposition Cipher
+///
+/// The message is written in rows under the letters of the key, then the columns are read
+/// in the alphabetical order of their key letters. Each key of a double transposition is
+/// applied one after another, and the Myszkowski variant reads the columns sharing a key
+/// letter together, row by row.
+///
+/// The struct is generated through the new() function.
+///
+pub struct Columnar {
+    keys: Vec<Vec<usize>>,
+    padding: Option<char>,
+}
+
+impl Columnar {
+    /// Initialize a columnar transposition cipher with the key, the columns under repeated
+    /// key letters are read from left to right and the last row is left incomplete.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid key:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::new("Zebras");
+    ///
+    /// assert!(columnar.is_ok());
+    /// ```
+    ///
+    /// - Initialization with an empty key:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::new("");
+    ///
+    /// assert!(columnar.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty.
+    pub fn new(key: &str) -> Result<Columnar, Error> {
+        Columnar::with_options(&[key], None, false)
+    }
+
+    /// Initialize a double columnar transposition cipher, the message is transposed
+    /// with the first key and then with the second key.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::double("Zebras", "Stripe").unwrap();
+    ///
+    /// assert_eq!("CAEENSOIAEDRLEFWEDREEVTOC", columnar.encipher("WEAREDISCOVEREDFLEEATONCE"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The keys must not be empty.
+    pub fn double(first_key: &str, second_key: &str) -> Result<Columnar, Error> {
+        Columnar::with_options(&[first_key, second_key], None, false)
+    }
+
+    /// Initialize a Myszkowski transposition cipher, the columns under the same key letter
+    /// are read together, row by row.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::myszkowski("Tomato").unwrap();
+    ///
+    /// assert_eq!("ROFOACDTEDSEEEACWEIVRLENE", columnar.encipher("WEAREDISCOVEREDFLEEATONCE"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty.
+    pub fn myszkowski(key: &str) -> Result<Columnar, Error> {
+        Columnar::with_options(&[key], None, true)
+    }
+
+    /// Initialize a columnar transposition cipher with its keys applied one after another,
+    /// the padding which completes the last row of the first table and the Myszkowski flag.
+    ///
+    /// The padding is kept by the decipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::with_options(&["Zebras"], Some('X'), false).unwrap();
+    ///
+    /// assert_eq!("EVLNXACDTXESEAXROFOXDEECXWIREE", columnar.encipher("WEAREDISCOVEREDFLEEATONCE"));
+    /// ```
+    ///
+    /// # Errors:
+    /// There must be at least one key and the keys must not be empty.
+    pub fn with_options(
+        keys: &[&str],
+        padding: Option<char>,
+        myszkowski: bool,
+    ) -> Result<Columnar, Error> {
+        if keys.is_empty() || keys.iter().any(|key| key.is_empty()) {
+            return Err(Error::EmptyKey);
+        }
+
+        Ok(Columnar {
+            keys: keys
+                .iter()
+                .map(|key| Columnar::ranks(key, myszkowski))
+                .collect(),
+            padding,
+        })
+    }
+
+    /// Enciphers a message with the columnar transposition cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::new("Zebras").unwrap();
+    ///
+    /// assert_eq!("EVLNACDTESEAROFODEECWIREE", columnar.encipher("WEAREDISCOVEREDFLEEATONCE"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let mut characters = message.chars().collect::<Vec<char>>();
+
+        if let Some(padding) = self.padding {
+            let width = self.keys[0].len();
+            while characters.len() % width != 0 {
+                characters.push(padding);
+            }
+        }
+
+        for ranks in self.keys.iter() {
+            characters = Columnar::order(ranks, characters.len())
+                .into_iter()
+                .map(|position| characters[position])
+                .collect();
+        }
+
+        characters.into_iter().collect()
+    }
+
+    /// Deciphers a cipher with the columnar transposition cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::columnar::Columnar;
+    /// let columnar = Columnar::new("Zebras").unwrap();
+    ///
+    /// assert_eq!("WEAREDISCOVEREDFLEEATONCE", columnar.decipher("EVLNACDTESEAROFODEECWIREE"));
+    /// ```
+    pub fn decipher(&self, cipher: &str) -> String {
+        let mut characters = cipher.chars().collect::<Vec<char>>();
+
+        for ranks in self.keys.iter().rev() {
+            let mut message = vec!['\0'; characters.len()];
+            for (character, position) in characters
+                .iter()
+                .zip(Columnar::order(ranks, characters.len()))
+            {
+                message[position] = *character;
+            }
+            characters = message;
+        }
+
+        characters.into_iter().collect()
+    }
+
+    /// Returns the rank of each column of the key, the columns under repeated key letters
+    /// share their rank in the Myszkowski variant.
+    fn ranks(key: &str, myszkowski: bool) -> Vec<usize> {
+        let key = key
+            .chars()
+            .map(|character| character.to_uppercase().next().unwrap_or(character))
+            .collect::<Vec<char>>();
+
+        let mut letters = key.clone();
+        letters.sort_unstable();
+        if myszkowski {
+            letters.dedup();
+        }
+
+        let mut ranks = Vec::with_capacity(key.len());
+        for (column, letter) in key.iter().enumerate() {
+            let first = letters
+                .iter()
+                .position(|other| other == letter)
+                .unwrap_or(0);
+            let repeated = match myszkowski {
+                true => 0,
+                false => key[..column]
+                    .iter()
+                    .filter(|other| *other == letter)
+                    .count(),
+            };
+            ranks.push(first + repeated);
+        }
+
+        ranks
+    }
+
+    /// Returns the positions of the characters of a message in the order they are read,
+    /// the last row of the table may be incomplete.
+    fn order(ranks: &[usize], length: usize) -> Vec<usize> {
+        let width = ranks.len();
+        let rows = (length + width - 1) / width;
+
+        let mut order = Vec::with_capacity(length);
+        for rank in 0..width {
+            let columns = (0..width)
+                .filter(|column| ranks[*column] == rank)
+                .collect::<Vec<usize>>();
+
+            for row in 0..rows {
+                for column in columns.iter() {
+                    let position = row * width + column;
+                    if position < length {
+                        order.push(position);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+}
+
+impl Cipher for Columnar {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Columnar::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Columnar::decipher(self, cipher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Columnar;
+    use crate::Error;
+
+    const MESSAGE: &str = "WEAREDISCOVEREDFLEEATONCE";
+
+    #[test]
+    fn encipher_test() {
+        let columnar = Columnar::new("ZEBRAS").unwrap();
+        assert_eq!("EVLNACDTESEAROFODEECWIREE", columnar.encipher(MESSAGE));
+    }
+
+    #[test]
+    fn decipher_test() {
+        let columnar = Columnar::new("ZEBRAS").unwrap();
+        assert_eq!(MESSAGE, columnar.decipher("EVLNACDTESEAROFODEECWIREE"));
+    }
+
+    #[test]
+    fn myszkowski_test() {
+        let columnar = Columnar::myszkowski("TOMATO").unwrap();
+
+        assert_eq!("ROFOACDTEDSEEEACWEIVRLENE", columnar.encipher(MESSAGE));
+        assert_eq!(MESSAGE, columnar.decipher("ROFOACDTEDSEEEACWEIVRLENE"));
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let message = "Hello, World! • Привет";
+
+        for key in ["A", "KEY", "BALLOON", "Transposition", "Περιστέρι"] {
+            for myszkowski in [false, true] {
+                let columnar = Columnar::with_options(&[key, "Second"], None, myszkowski).unwrap();
+                assert_eq!(message, columnar.decipher(&columnar.encipher(message)));
+            }
+        }
+    }
+
+    #[test]
+    fn padding_test() {
+        let columnar = Columnar::with_options(&["ZEBRAS"], Some('X'), false).unwrap();
+        let cipher = columnar.encipher(MESSAGE);
+
+        assert_eq!(30, cipher.len());
+        assert_eq!("WEAREDISCOVEREDFLEEATONCEXXXXX", columnar.decipher(&cipher));
+    }
+
+    #[test]
+    fn invalid_test() {
+        assert_eq!(Some(Error::EmptyKey), Columnar::double("KEY", "").err());
+        assert_eq!(
+            Some(Error::EmptyKey),
+            Columnar::with_options(&[], None, false).err()
+        );
+    }
+}
@@ -0,0 +1,520 @@
+use crate::ciphers::Cipher;
+use crate::Error;
+
+/// The models of the Enigma machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// Enigma I of the army and the air force, three rotors out of I to V.
+    I,
+    /// Enigma M3 of the navy, three rotors out of I to VIII.
+    M3,
+    /// Enigma M4 of the submarines, the Beta or Gamma rotor and three rotors out of I to VIII
+    /// with a thin reflector.
+    M4,
+}
+
+/// The historical rotors of the Enigma machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotor {
+    I,
+    II,
+    III,
+    IV,
+    V,
+    VI,
+    VII,
+    VIII,
+    /// The fourth rotor of the M4, it never steps.
+    Beta,
+    /// The fourth rotor of the M4, it never steps.
+    Gamma,
+}
+
+impl Rotor {
+    fn wiring(self) -> &'static [u8; 26] {
+        match self {
+            Rotor::I => b"EKMFLGDQVZNTOWYHXUSPAIBRCJ",
+            Rotor::II => b"AJDKSIRUXBLHWTMCQGZNPYFVOE",
+            Rotor::III => b"BDFHJLCPRTXVZNYEIWGAKMUSQO",
+            Rotor::IV => b"ESOVPZJAYQUIRHXLNFTGKDCMWB",
+            Rotor::V => b"VZBRGITYUPSDNHLXAWMJQOFECK",
+            Rotor::VI => b"JPGVOUMFYQBENHZRDKASXLICTW",
+            Rotor::VII => b"NZJHGRCXMYSWBOUFAIVLPEKQDT",
+            Rotor::VIII => b"FKQHTLXOCBJSPDZRAMEWNIUYGV",
+            Rotor::Beta => b"LEYJVCNIXWPBQMDRTAKZGFUHOS",
+            Rotor::Gamma => b"FSOKANUERHMBTIJYCWLQPZXVGD",
+        }
+    }
+
+    /// Returns the letters shown by the rotor when it makes the next rotor step.
+    fn notches(self) -> &'static [u8] {
+        match self {
+            Rotor::I => b"Q",
+            Rotor::II => b"E",
+            Rotor::III => b"V",
+            Rotor::IV => b"J",
+            Rotor::V => b"Z",
+            Rotor::VI | Rotor::VII | Rotor::VIII => b"ZM",
+            Rotor::Beta | Rotor::Gamma => b"",
+        }
+    }
+
+    fn is_greek(self) -> bool {
+        matches!(self, Rotor::Beta | Rotor::Gamma)
+    }
+}
+
+/// The historical reflectors of the Enigma machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reflector {
+    B,
+    C,
+    /// The thin B reflector of the M4.
+    BThin,
+    /// The thin C reflector of the M4.
+    CThin,
+}
+
+impl Reflector {
+    fn wiring(self) -> &'static [u8; 26] {
+        match self {
+            Reflector::B => b"YRUHQSLDPXNGOKMIEBFZCWVJAT",
+            Reflector::C => b"FVPJIAOYEDRZXWGCTKUQSBNMHL",
+            Reflector::BThin => b"ENKQAUYWJICOPBLMDXZVFTHRGS",
+            Reflector::CThin => b"RDOBJNTKVEHMLFCWZAXGYIPSUQ",
+        }
+    }
+
+    fn is_thin(self) -> bool {
+        matches!(self, Reflector::BThin | Reflector::CThin)
+    }
+}
+
+/// A rotor mounted in the machine with its ring setting and its starting position.
+struct Wheel {
+    rotor: Rotor,
+    forward: [usize; 26],
+    backward: [usize; 26],
+    ring: usize,
+    position: usize,
+}
+
+impl Wheel {
+    fn new(rotor: Rotor, ring: usize, position: usize) -> Wheel {
+        let mut forward = [0; 26];
+        let mut backward = [0; 26];
+        for (input, output) in rotor.wiring().iter().enumerate() {
+            forward[input] = (output - b'A') as usize;
+            backward[(output - b'A') as usize] = input;
+        }
+
+        Wheel {
+            rotor,
+            forward,
+            backward,
+            ring,
+            position,
+        }
+    }
+
+    fn at_notch(&self, position: usize) -> bool {
+        self.rotor.notches().contains(&(b'A' + position as u8))
+    }
+
+    fn pass(wiring: &[usize; 26], signal: usize, shift: usize) -> usize {
+        (wiring[(signal + shift) % 26] + 26 - shift) % 26
+    }
+}
+
+/// Enigma Machine
+///
+/// Each letter goes through the plugboard, the rotors from right to left, the reflector,
+/// the rotors from left to right and the plugboard again. The rotors step before each
+/// letter, the middle rotor stepping twice in a row when it reaches its notch.
+///
+/// The machine is reciprocal, so enciphering and deciphering are the same operation.
+///
+/// The struct is generated through the new() function.
+///
+pub struct Enigma {
+    wheels: Vec<Wheel>,
+    reflector: [usize; 26],
+    plugboard: [usize; 26],
+}
+
+impl Enigma {
+    /// Initialize an Enigma machine with its model, its reflector, its rotors from left
+    /// to right, the ring settings and the starting positions as a letter per rotor,
+    /// and the plugboard as pairs of letters separated by spaces.
+    ///
+    /// # Examples:
+    /// - Initialization of an Enigma I:
+    /// ```
+    /// use cienli::ciphers::enigma::{Enigma, Model, Reflector, Rotor};
+    /// let enigma = Enigma::new(Model::I, Reflector::B, &[Rotor::II, Rotor::IV, Rotor::V], "BUL", "BLA", "AV BS CG DL FU HZ IN KM OW RX");
+    ///
+    /// assert!(enigma.is_ok());
+    /// ```
+    ///
+    /// - Initialization of an Enigma I with a navy rotor:
+    /// ```
+    /// use cienli::ciphers::enigma::{Enigma, Model, Reflector, Rotor};
+    /// let enigma = Enigma::new(Model::I, Reflector::B, &[Rotor::VI, Rotor::IV, Rotor::V], "AAA", "AAA", "");
+    ///
+    /// assert!(enigma.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// - The rotors must be distinct and available in the model, the Beta or Gamma rotor
+    ///   is only the leftmost rotor of the M4.
+    /// - The reflector must be thin in the M4 and thick in the other models.
+    /// - The ring settings and the starting positions must have a latin letter per rotor.
+    /// - The plugboard must be made of pairs of latin letters, each letter used once.
+    pub fn new(
+        model: Model,
+        reflector: Reflector,
+        rotors: &[Rotor],
+        rings: &str,
+        positions: &str,
+        plugboard: &str,
+    ) -> Result<Enigma, Error> {
+        Enigma::check_rotors(model, rotors)?;
+
+        if reflector.is_thin() != (model == Model::M4) {
+            return Err(Error::InvalidKey("Thin reflectors are only used in the M4"));
+        }
+
+        let rings = Enigma::letters(rings, rotors.len()).ok_or(Error::InvalidKey(
+            "Ring settings must have a letter per rotor",
+        ))?;
+        let positions = Enigma::letters(positions, rotors.len()).ok_or(Error::InvalidKey(
+            "Starting positions must have a letter per rotor",
+        ))?;
+
+        let wheels = rotors
+            .iter()
+            .zip(rings.iter().zip(positions.iter()))
+            .map(|(rotor, (ring, position))| Wheel::new(*rotor, *ring, *position))
+            .collect();
+
+        let mut wiring = [0; 26];
+        for (input, output) in reflector.wiring().iter().enumerate() {
+            wiring[input] = (output - b'A') as usize;
+        }
+
+        Ok(Enigma {
+            wheels,
+            reflector: wiring,
+            plugboard: Enigma::plugboard(plugboard)?,
+        })
+    }
+
+    /// Enciphers a message with the Enigma machine, starting from the starting positions.
+    /// The characters which are not latin letters are kept and do not step the rotors.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::enigma::{Enigma, Model, Reflector, Rotor};
+    /// let enigma = Enigma::new(Model::I, Reflector::B, &[Rotor::I, Rotor::II, Rotor::III], "AAA", "AAA", "").unwrap();
+    ///
+    /// assert_eq!("BDZGO", enigma.encipher("AAAAA"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let mut positions = self
+            .wheels
+            .iter()
+            .map(|wheel| wheel.position)
+            .collect::<Vec<usize>>();
+
+        message
+            .chars()
+            .map(|character| {
+                if !character.is_ascii_alphabetic() {
+                    return character;
+                }
+
+                self.step(&mut positions);
+                let letter = self.press(
+                    (character.to_ascii_uppercase() as u8 - b'A') as usize,
+                    &positions,
+                );
+                let output = (b'A' + letter as u8) as char;
+
+                match character.is_ascii_lowercase() {
+                    true => output.to_ascii_lowercase(),
+                    false => output,
+                }
+            })
+            .collect()
+    }
+
+    /// Deciphers a cipher with the Enigma machine, starting from the starting positions.
+    /// The characters which are not latin letters are kept and do not step the rotors.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::enigma::{Enigma, Model, Reflector, Rotor};
+    /// let enigma = Enigma::new(Model::M3, Reflector::C, &[Rotor::VIII, Rotor::I, Rotor::VI], "KAZ", "HQM", "PO ML IU KJ NH YT").unwrap();
+    ///
+    /// assert_eq!("Enigma, 1930", enigma.decipher(&enigma.encipher("Enigma, 1930")));
+    /// ```
+    pub fn decipher(&self, cipher: &str) -> String {
+        self.encipher(cipher)
+    }
+
+    fn check_rotors(model: Model, rotors: &[Rotor]) -> Result<(), Error> {
+        let (count, last) = match model {
+            Model::I => (3, Rotor::V),
+            Model::M3 => (3, Rotor::VIII),
+            Model::M4 => (4, Rotor::VIII),
+        };
+
+        if rotors.len() != count {
+            return Err(Error::InvalidKey(
+                "The M4 has four rotors, the other models have three",
+            ));
+        }
+
+        let stepping = &rotors[count - 3..];
+        if model == Model::M4 && !rotors[0].is_greek() {
+            return Err(Error::InvalidKey(
+                "The leftmost rotor of the M4 must be Beta or Gamma",
+            ));
+        }
+
+        if stepping
+            .iter()
+            .any(|rotor| rotor.is_greek() || *rotor as u8 > last as u8)
+        {
+            return Err(Error::InvalidKey("Rotor is not available in this model"));
+        }
+
+        if (1..stepping.len()).any(|index| stepping[..index].contains(&stepping[index])) {
+            return Err(Error::InvalidKey("Rotors must be distinct"));
+        }
+
+        Ok(())
+    }
+
+    fn letters(settings: &str, count: usize) -> Option<Vec<usize>> {
+        let letters = settings
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .map(|character| match character.is_ascii_alphabetic() {
+                true => Some((character.to_ascii_uppercase() as u8 - b'A') as usize),
+                false => None,
+            })
+            .collect::<Option<Vec<usize>>>()?;
+
+        match letters.len() == count {
+            true => Some(letters),
+            false => None,
+        }
+    }
+
+    fn plugboard(pairs: &str) -> Result<[usize; 26], Error> {
+        let mut plugboard = [0; 26];
+        for (letter, plug) in plugboard.iter_mut().enumerate() {
+            *plug = letter;
+        }
+
+        for pair in pairs.split_whitespace() {
+            let letters = Enigma::letters(pair, 2).ok_or(Error::InvalidKey(
+                "Plugboard must be made of pairs of latin letters",
+            ))?;
+            let (first, second) = (letters[0], letters[1]);
+
+            if first == second || plugboard[first] != first || plugboard[second] != second {
+                return Err(Error::InvalidKey("Each letter can only be plugged once"));
+            }
+
+            plugboard[first] = second;
+            plugboard[second] = first;
+        }
+
+        Ok(plugboard)
+    }
+
+    /// Steps the three rightmost rotors, the middle rotor steps with the left rotor when
+    /// it is at its notch.
+    fn step(&self, positions: &mut [usize]) {
+        let right = positions.len() - 1;
+        let (left, middle) = (right - 2, right - 1);
+
+        let middle_at_notch = self.wheels[middle].at_notch(positions[middle]);
+        if middle_at_notch {
+            positions[left] = (positions[left] + 1) % 26;
+        }
+        if middle_at_notch || self.wheels[right].at_notch(positions[right]) {
+            positions[middle] = (positions[middle] + 1) % 26;
+        }
+        positions[right] = (positions[right] + 1) % 26;
+    }
+
+    fn press(&self, letter: usize, positions: &[usize]) -> usize {
+        let shifts = self
+            .wheels
+            .iter()
+            .zip(positions)
+            .map(|(wheel, position)| (position + 26 - wheel.ring) % 26)
+            .collect::<Vec<usize>>();
+
+        let mut signal = self.plugboard[letter];
+        for (wheel, shift) in self.wheels.iter().zip(shifts.iter()).rev() {
+            signal = Wheel::pass(&wheel.forward, signal, *shift);
+        }
+        signal = self.reflector[signal];
+        for (wheel, shift) in self.wheels.iter().zip(shifts.iter()) {
+            signal = Wheel::pass(&wheel.backward, signal, *shift);
+        }
+
+        self.plugboard[signal]
+    }
+}
+
+impl Cipher for Enigma {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Enigma::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Enigma::decipher(self, cipher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Enigma, Model, Reflector, Rotor};
+
+    #[test]
+    fn encipher_test() {
+        let enigma = Enigma::new(
+            Model::I,
+            Reflector::B,
+            &[Rotor::I, Rotor::II, Rotor::III],
+            "AAA",
+            "AAA",
+            "",
+        )
+        .unwrap();
+
+        assert_eq!("BDZGO", enigma.encipher("AAAAA"));
+    }
+
+    #[test]
+    fn double_stepping_test() {
+        let enigma = Enigma::new(
+            Model::M3,
+            Reflector::B,
+            &[Rotor::I, Rotor::II, Rotor::III],
+            "AAA",
+            "ADU",
+            "",
+        )
+        .unwrap();
+        let mut positions = vec![0, 3, 20];
+
+        for expected in [[0, 3, 21], [0, 4, 22], [1, 5, 23]] {
+            enigma.step(&mut positions);
+            assert_eq!(expected.to_vec(), positions);
+        }
+    }
+
+    #[test]
+    fn barbarossa_test() {
+        let enigma = Enigma::new(
+            Model::I,
+            Reflector::B,
+            &[Rotor::II, Rotor::IV, Rotor::V],
+            "BUL",
+            "BLA",
+            "AV BS CG DL FU HZ IN KM OW RX",
+        )
+        .unwrap();
+
+        assert_eq!(
+            "AUFKL XABTE ILUNG XVONX KURTI NOWAX KURTI NOWAX NORDW ESTLX SEBEZ XSEBE ZXUAF FLIEG \
+             ERSTR ASZER IQTUN GXDUB ROWKI XDUBR OWKIX OPOTS CHKAX OPOTS CHKAX UMXEI NSAQT DREIN \
+             ULLXU HRANG ETRET ENXAN GRIFF XINFX RGTX",
+            enigma.decipher(
+                "EDPUD NRGYS ZRCXN UYTPO MRMBO FKTBZ REZKM LXLVE FGUEY SIOZV EQMIK UBPMM YLKLT \
+                 TDEIS MDICA GYKUA CTCDO MOHWX MUUIA UBSTS LRNBZ SZWNR FXWFY SSXJZ VIJHI DISHP \
+                 RKLKA YUPAD TXQSP INQMA TLPIF SVKDA SCTAC DPBOP VHJK"
+            )
+        );
+    }
+
+    #[test]
+    fn m4_test() {
+        let enigma = Enigma::new(
+            Model::M4,
+            Reflector::BThin,
+            &[Rotor::Beta, Rotor::II, Rotor::IV, Rotor::I],
+            "AAAV",
+            "VJNA",
+            "AT BL DF GJ HM NW OP QY RZ VX",
+        )
+        .unwrap();
+
+        assert_eq!(
+            "VONVONJLOOKSJHFFTTTEINSEINSDREIZWOYYQNNSNEUNINHALTXX",
+            enigma.decipher("NCZWVUSXPNYMINHZXMQXSFWXWLKJAHSHNMCOCCAKUQPMKCSMHKSE")
+        );
+    }
+
+    #[test]
+    fn m4_compatibility_test() {
+        let m3 = Enigma::new(
+            Model::M3,
+            Reflector::B,
+            &[Rotor::I, Rotor::II, Rotor::III],
+            "AAA",
+            "AAA",
+            "",
+        )
+        .unwrap();
+        let m4 = Enigma::new(
+            Model::M4,
+            Reflector::BThin,
+            &[Rotor::Beta, Rotor::I, Rotor::II, Rotor::III],
+            "AAAA",
+            "AAAA",
+            "",
+        )
+        .unwrap();
+
+        let message = "The Beta rotor at A with the thin B reflector acts like the B reflector";
+        assert_eq!(m3.encipher(message), m4.encipher(message));
+    }
+
+    #[test]
+    fn invalid_test() {
+        let rotors = [Rotor::I, Rotor::II, Rotor::III];
+
+        assert!(Enigma::new(Model::M3, Reflector::BThin, &rotors, "AAA", "AAA", "").is_err());
+        assert!(Enigma::new(Model::M4, Reflector::BThin, &rotors, "AAA", "AAA", "").is_err());
+        assert!(Enigma::new(
+            Model::M3,
+            Reflector::B,
+            &[Rotor::I, Rotor::I, Rotor::II],
+            "AAA",
+            "AAA",
+            ""
+        )
+        .is_err());
+        assert!(Enigma::new(
+            Model::M3,
+            Reflector::B,
+            &[Rotor::Beta, Rotor::I, Rotor::II],
+            "AAA",
+            "AAA",
+            ""
+        )
+        .is_err());
+        assert!(Enigma::new(Model::M3, Reflector::B, &rotors, "AA", "AAA", "").is_err());
+        assert!(Enigma::new(Model::M3, Reflector::B, &rotors, "AAA", "A1A", "").is_err());
+        assert!(Enigma::new(Model::M3, Reflector::B, &rotors, "AAA", "AAA", "AB BC").is_err());
+        assert!(Enigma::new(Model::M3, Reflector::B, &rotors, "AAA", "AAA", "AA").is_err());
+        assert!(Enigma::new(Model::M3, Reflector::B, &rotors, "AAA", "AAA", "ABC").is_err());
+    }
+}
@@ -0,0 +1,544 @@
+use crate::ciphers::Cipher;
+use crate::common::{Alphabet, Case};
+use crate::Error;
+
+/// The largest number of known blocks tried by [`Hill::solve_known_plaintext`].
+const MAX_KNOWN_BLOCKS: usize = 16;
+
+/// Hill Cipher
+///
+/// The struct is generated through the new() function.
+///
+pub struct Hill {
+    matrix: Vec<Vec<usize>>,
+    inverse: Vec<Vec<usize>>,
+    alphabet: Alphabet,
+    padding: usize,
+}
+
+impl Hill {
+    /// Initialize a hill cipher with a square key matrix over the latin alphabet,
+    /// the messages are padded with 'X'.
+    ///
+    /// # Examples:
+    /// - Initialization with an invertible matrix:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// let hill = Hill::new(vec![vec![3, 3], vec![2, 5]]);
+    ///
+    /// assert!(hill.is_ok());
+    /// ```
+    ///
+    /// - Initialization with a matrix whose determinant is not co-prime with 26:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// let hill = Hill::new(vec![vec![2, 4], vec![1, 3]]);
+    ///
+    /// assert!(hill.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The matrix must be square, its values must be lower than 26 and its determinant
+    /// must be co-prime with 26.
+    pub fn new(matrix: Vec<Vec<usize>>) -> Result<Hill, Error> {
+        Hill::with_alphabet(matrix, Alphabet::latin(), 'X')
+    }
+
+    /// Initialize a hill cipher with a square key matrix over a custom alphabet
+    /// and the letter padding the messages to a multiple of the size of the matrix.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// use cienli::common::Alphabet;
+    /// let hill = Hill::with_alphabet(vec![vec![1, 2], vec![3, 5]], Alphabet::alphanumeric(), '0').unwrap();
+    ///
+    /// assert_eq!("HELLO 420", hill.decipher(&hill.encipher("HELLO 42")).unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The matrix must be square, its values must be lower than the length of the alphabet,
+    /// its determinant must be co-prime with the length of the alphabet and the padding
+    /// must be a letter of the alphabet.
+    pub fn with_alphabet(
+        matrix: Vec<Vec<usize>>,
+        alphabet: Alphabet,
+        padding: char,
+    ) -> Result<Hill, Error> {
+        Hill::key_checker(&matrix, alphabet.len())?;
+
+        let (padding, _) = alphabet
+            .index_of(padding)
+            .ok_or(Error::UnsupportedCharacter(padding))?;
+        let inverse = invert(&matrix, alphabet.len())
+            .ok_or(Error::InvalidKey("The key matrix must be invertible"))?;
+
+        Ok(Hill {
+            matrix,
+            inverse,
+            alphabet,
+            padding,
+        })
+    }
+
+    /// Returns the key matrix.
+    pub fn matrix(&self) -> &[Vec<usize>] {
+        &self.matrix
+    }
+
+    /// Enciphers a message with the hill cipher.
+    ///
+    /// The letters are enciphered by blocks of the size of the matrix, the other characters
+    /// are kept in place and the last block is padded.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// let hill = Hill::new(vec![vec![6, 24, 1], vec![13, 16, 10], vec![20, 17, 15]]).unwrap();
+    ///
+    /// assert_eq!("Poh!", hill.encipher("Act!"));
+    /// assert_eq!("POHDNI", hill.encipher("ACTN"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let mut characters = message.chars().collect::<Vec<char>>();
+        let mut letters = self.letters(&characters);
+
+        while letters.len() % self.matrix.len() != 0 {
+            characters.push(self.alphabet.letter(self.padding, Case::Upper));
+            letters.push((characters.len() - 1, self.padding, Case::Upper));
+        }
+
+        self.transform(characters, &letters, &self.matrix)
+    }
+
+    /// Deciphers a cipher with the hill cipher, the padding is kept in the message.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// let hill = Hill::new(vec![vec![6, 24, 1], vec![13, 16, 10], vec![20, 17, 15]]).unwrap();
+    ///
+    /// assert_eq!("Act!", hill.decipher("Poh!").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The number of letters of the cipher must be a multiple of the size of the matrix.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        let characters = cipher.chars().collect::<Vec<char>>();
+        let letters = self.letters(&characters);
+
+        if letters.len() % self.matrix.len() != 0 {
+            return Err(Error::MalformedCiphertext {
+                position: characters.len(),
+            });
+        }
+
+        Ok(self.transform(characters, &letters, &self.inverse))
+    }
+
+    /// Recovers the key matrix of size n from a known plaintext and its cipher over the
+    /// latin alphabet.
+    ///
+    /// The letters of both texts are split in blocks of n letters, n plaintext blocks
+    /// forming an invertible matrix are enough to solve the key.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// let hill = Hill::new(vec![vec![3, 3], vec![2, 5]]).unwrap();
+    /// let cipher = hill.encipher("Help me");
+    ///
+    /// let solved = Hill::solve_known_plaintext("Help me", &cipher, 2).unwrap();
+    /// assert_eq!(hill.matrix(), solved.matrix());
+    /// ```
+    ///
+    /// # Errors:
+    /// The plaintext must contain n blocks forming an invertible matrix and every block must
+    /// agree with the recovered key.
+    pub fn solve_known_plaintext(plain: &str, cipher: &str, n: usize) -> Result<Hill, Error> {
+        Hill::solve_known_plaintext_with(plain, cipher, n, Alphabet::latin(), 'X')
+    }
+
+    /// Recovers the key matrix of size n from a known plaintext and its cipher over a
+    /// custom alphabet, the solved cipher pads the messages with the given letter.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::hill::Hill;
+    /// use cienli::common::Alphabet;
+    /// let hill = Hill::with_alphabet(vec![vec![1, 2], vec![3, 5]], Alphabet::alphanumeric(), '0').unwrap();
+    /// let plain = "Meet me at gate 4 at 9 pm";
+    ///
+    /// let solved =
+    ///     Hill::solve_known_plaintext_with(plain, &hill.encipher(plain), 2, Alphabet::alphanumeric(), '0')
+    ///         .unwrap();
+    /// assert_eq!(hill.matrix(), solved.matrix());
+    /// ```
+    ///
+    /// # Errors:
+    /// The plaintext must contain n blocks forming an invertible matrix, every block must
+    /// agree with the recovered key and the padding must be a letter of the alphabet.
+    pub fn solve_known_plaintext_with(
+        plain: &str,
+        cipher: &str,
+        n: usize,
+        alphabet: Alphabet,
+        padding: char,
+    ) -> Result<Hill, Error> {
+        let modulus = alphabet.len();
+        let index = |character| alphabet.index_of(character).map(|(index, _)| index);
+
+        if n == 0 {
+            return Err(Error::InvalidKey("The size of the matrix cannot be zero"));
+        }
+
+        let plain = plain.chars().filter_map(index).collect::<Vec<usize>>();
+        let cipher = cipher.chars().filter_map(index).collect::<Vec<usize>>();
+        let plain_blocks = plain.chunks_exact(n).collect::<Vec<&[usize]>>();
+        let cipher_blocks = cipher.chunks_exact(n).collect::<Vec<&[usize]>>();
+        let known_blocks = plain_blocks.len().min(cipher_blocks.len());
+
+        // The chosen blocks are the columns of P and C, the key is C * P^-1.
+        let matrix = combinations(known_blocks.min(MAX_KNOWN_BLOCKS), n)
+            .into_iter()
+            .find_map(|chosen| {
+                let plain_inverse = invert(&as_columns(&plain_blocks, &chosen), modulus)?;
+                Some(multiply(
+                    &as_columns(&cipher_blocks, &chosen),
+                    &plain_inverse,
+                    modulus,
+                ))
+            })
+            .ok_or(Error::InvalidKey(
+                "The known plaintext does not contain enough blocks to solve the key",
+            ))?;
+
+        let hill = Hill::with_alphabet(matrix, alphabet, padding)?;
+        if plain_blocks
+            .iter()
+            .zip(cipher_blocks.iter())
+            .all(|(plain, cipher)| multiply_vector(&hill.matrix, plain, modulus) == *cipher)
+        {
+            Ok(hill)
+        } else {
+            Err(Error::InvalidKey(
+                "The known plaintext and cipher do not match any key",
+            ))
+        }
+    }
+
+    /// Returns the positions, values and cases of the letters of the alphabet.
+    fn letters(&self, characters: &[char]) -> Vec<(usize, usize, Case)> {
+        characters
+            .iter()
+            .enumerate()
+            .filter_map(|(position, character)| {
+                self.alphabet
+                    .index_of(*character)
+                    .map(|(index, case)| (position, index, case))
+            })
+            .collect()
+    }
+
+    fn transform(
+        &self,
+        mut characters: Vec<char>,
+        letters: &[(usize, usize, Case)],
+        matrix: &[Vec<usize>],
+    ) -> String {
+        for block in letters.chunks(matrix.len()) {
+            let values = block.iter().map(|letter| letter.1).collect::<Vec<usize>>();
+            let result = multiply_vector(matrix, &values, self.alphabet.len());
+
+            for ((position, _, case), value) in block.iter().zip(result) {
+                characters[*position] = self.alphabet.letter(value, *case);
+            }
+        }
+
+        characters.into_iter().collect()
+    }
+
+    fn key_checker(matrix: &[Vec<usize>], modulus: usize) -> Result<(), Error> {
+        if matrix.is_empty() || matrix.iter().any(|row| row.len() != matrix.len()) {
+            return Err(Error::InvalidKey("The key matrix must be square"));
+        }
+
+        if matrix.iter().flatten().any(|value| *value >= modulus) {
+            return Err(Error::InvalidKey(
+                "The values of the key matrix must be lower than the length of the alphabet",
+            ));
+        }
+
+        let determinant = determinant(matrix, modulus);
+        if gcd(determinant, modulus) == 1 {
+            Ok(())
+        } else {
+            Err(Error::KeyNotCoprime {
+                key: determinant,
+                modulus,
+            })
+        }
+    }
+}
+
+impl Cipher for Hill {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Hill::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Hill::decipher(self, cipher)
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+fn modular_inverse(value: usize, modulus: usize) -> Option<usize> {
+    (0..modulus).find(|inverse| (value * inverse) % modulus == 1 % modulus)
+}
+
+/// Makes the rows of a matrix upper triangular in its first n columns with row operations
+/// that keep the determinant (up to the sign), and returns the sign.
+///
+/// The pivots are built with the euclidean algorithm, so the modulus does not need to be prime.
+fn triangularize(rows: &mut [Vec<usize>], n: usize, modulus: usize) -> bool {
+    let mut negative = false;
+
+    for column in 0..n {
+        for row in column + 1..n {
+            while rows[row][column] != 0 {
+                let quotient = rows[column][column] / rows[row][column];
+                let (pivot, other) = (rows[row].clone(), &mut rows[column]);
+                for (value, subtracted) in other.iter_mut().zip(pivot) {
+                    *value = (*value + modulus - quotient * subtracted % modulus) % modulus;
+                }
+                rows.swap(column, row);
+                negative = !negative;
+            }
+        }
+    }
+
+    negative
+}
+
+fn determinant(matrix: &[Vec<usize>], modulus: usize) -> usize {
+    let mut rows = matrix.to_vec();
+    let negative = triangularize(&mut rows, matrix.len(), modulus);
+
+    let product = (0..matrix.len()).fold(1 % modulus, |product, index| {
+        product * rows[index][index] % modulus
+    });
+
+    match negative {
+        true => (modulus - product) % modulus,
+        false => product,
+    }
+}
+
+/// Returns the inverse of a matrix modulo the modulus, or None if it is not invertible.
+fn invert(matrix: &[Vec<usize>], modulus: usize) -> Option<Vec<Vec<usize>>> {
+    let n = matrix.len();
+    let mut rows = matrix
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let mut row = row.clone();
+            row.extend((0..n).map(|column| usize::from(column == index)));
+            row
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    triangularize(&mut rows, n, modulus);
+
+    for column in (0..n).rev() {
+        let inverse = modular_inverse(rows[column][column], modulus)?;
+        for value in rows[column].iter_mut() {
+            *value = *value * inverse % modulus;
+        }
+
+        for row in 0..column {
+            let factor = rows[row][column];
+            let pivot = rows[column].clone();
+            for (value, subtracted) in rows[row].iter_mut().zip(pivot) {
+                *value = (*value + modulus - factor * subtracted % modulus) % modulus;
+            }
+        }
+    }
+
+    Some(rows.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+fn multiply(a: &[Vec<usize>], b: &[Vec<usize>], modulus: usize) -> Vec<Vec<usize>> {
+    a.iter()
+        .map(|row| {
+            (0..b.first().map_or(0, Vec::len))
+                .map(|column| {
+                    row.iter()
+                        .zip(b.iter())
+                        .map(|(value, other)| value * other[column])
+                        .sum::<usize>()
+                        % modulus
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn multiply_vector(matrix: &[Vec<usize>], vector: &[usize], modulus: usize) -> Vec<usize> {
+    matrix
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(vector)
+                .map(|(value, other)| value * other)
+                .sum::<usize>()
+                % modulus
+        })
+        .collect()
+}
+
+/// Returns the matrix whose columns are the chosen blocks.
+fn as_columns(blocks: &[&[usize]], chosen: &[usize]) -> Vec<Vec<usize>> {
+    (0..chosen.len())
+        .map(|row| chosen.iter().map(|block| blocks[*block][row]).collect())
+        .collect()
+}
+
+/// Returns the sets of k indices lower than n, in lexicographic order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut current = Vec::with_capacity(k);
+
+    fn extend(
+        start: usize,
+        n: usize,
+        k: usize,
+        current: &mut Vec<usize>,
+        result: &mut Vec<Vec<usize>>,
+    ) {
+        if current.len() == k {
+            result.push(current.clone());
+            return;
+        }
+
+        for index in start..n {
+            current.push(index);
+            extend(index + 1, n, k, current, result);
+            current.pop();
+        }
+    }
+
+    extend(0, n, k, &mut current, &mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{determinant, invert, multiply, Hill};
+    use crate::common::Alphabet;
+    use crate::Error;
+
+    const KEY: [[usize; 3]; 3] = [[6, 24, 1], [13, 16, 10], [20, 17, 15]];
+
+    fn key() -> Vec<Vec<usize>> {
+        KEY.iter().map(|row| row.to_vec()).collect()
+    }
+
+    #[test]
+    fn encipher_test() {
+        let hill = Hill::new(key()).unwrap();
+        assert_eq!("POH", hill.encipher("ACT"));
+        assert_eq!("FIN", hill.encipher("CAT"));
+    }
+
+    #[test]
+    fn decipher_test() {
+        let hill = Hill::new(key()).unwrap();
+        assert_eq!("ACT", hill.decipher("POH").unwrap());
+    }
+
+    #[test]
+    fn padding_test() {
+        let hill = Hill::new(vec![vec![3, 3], vec![2, 5]]).unwrap();
+        let cipher = hill.encipher("Hi there");
+
+        assert_eq!(9, cipher.chars().count());
+        assert_eq!("Hi thereX", hill.decipher(&cipher).unwrap());
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 3 }),
+            hill.decipher("Abc")
+        );
+    }
+
+    #[test]
+    fn invalid_key_test() {
+        assert_eq!(
+            Some(Error::KeyNotCoprime {
+                key: 2,
+                modulus: 26
+            }),
+            Hill::new(vec![vec![2, 4], vec![1, 3]]).err()
+        );
+        assert!(Hill::new(vec![vec![1, 2, 3], vec![4, 5, 6]]).is_err());
+        assert!(Hill::new(vec![vec![26, 1], vec![1, 1]]).is_err());
+        assert!(Hill::new(Vec::new()).is_err());
+        assert!(Hill::with_alphabet(key(), Alphabet::latin(), '#').is_err());
+    }
+
+    #[test]
+    fn inverse_test() {
+        for modulus in [26, 29, 31] {
+            let inverse = invert(&key(), modulus).unwrap();
+            let identity = multiply(&key(), &inverse, modulus);
+
+            assert_eq!(vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]], identity);
+        }
+        assert_eq!(25, determinant(&key(), 26));
+    }
+
+    #[test]
+    fn custom_alphabet_test() {
+        let matrix = vec![vec![5, 17, 3], vec![8, 1, 14], vec![2, 9, 5]];
+        let hill = Hill::with_alphabet(matrix, Alphabet::greek(), 'Ω').unwrap();
+        let message = "Καλημέρα κόσμε";
+
+        assert_eq!(
+            "Καλημέρα κόσμεΩ",
+            hill.decipher(&hill.encipher(message)).unwrap()
+        );
+    }
+
+    #[test]
+    fn known_plaintext_test() {
+        let hill = Hill::new(key()).unwrap();
+        let plain = "Retreat now, the enemy knows our position";
+        let solved = Hill::solve_known_plaintext(plain, &hill.encipher(plain), 3).unwrap();
+
+        assert_eq!(hill.matrix(), solved.matrix());
+        assert!(Hill::solve_known_plaintext("aaaaaa", "bbbbbb", 3).is_err());
+    }
+
+    #[test]
+    fn known_plaintext_custom_alphabet_test() {
+        let matrix = vec![vec![5, 17, 3], vec![8, 1, 14], vec![2, 9, 5]];
+        let hill = Hill::with_alphabet(matrix, Alphabet::greek(), 'Ω').unwrap();
+        let plain = "Η θάλασσα ήταν ήσυχη όταν φτάσαμε στο λιμάνι το πρωί";
+        let solved = Hill::solve_known_plaintext_with(
+            plain,
+            &hill.encipher(plain),
+            3,
+            Alphabet::greek(),
+            'Ω',
+        )
+        .unwrap();
+
+        assert_eq!(hill.matrix(), solved.matrix());
+        assert_eq!(hill.encipher(plain), solved.encipher(plain));
+        assert!(Hill::solve_known_plaintext(plain, &hill.encipher(plain), 3).is_err());
+    }
+}
@@ -1,18 +1,85 @@
+#[cfg(feature = "adfgvx")]
+pub mod adfgvx;
 #[cfg(feature = "affine")]
 pub mod affine;
 #[cfg(feature = "atbash")]
 pub mod atbash;
 #[cfg(feature = "bacon")]
 pub mod bacon;
+#[cfg(feature = "bifid")]
+pub mod bifid;
 #[cfg(feature = "caesar")]
 pub mod caesar;
+#[cfg(feature = "columnar")]
+pub mod columnar;
+#[cfg(feature = "enigma")]
+pub mod enigma;
+#[cfg(feature = "hill")]
+pub mod hill;
+#[cfg(feature = "playfair")]
+pub mod playfair;
 #[cfg(feature = "polybius_square")]
 pub mod polybius_square;
+#[cfg(feature = "rail_fence")]
+pub mod rail_fence;
 #[cfg(feature = "rot")]
 pub mod rot;
 #[cfg(feature = "scytale")]
 pub mod scytale;
+#[cfg(feature = "substitution")]
+pub mod substitution;
+#[cfg(feature = "trifid")]
+pub mod trifid;
 #[cfg(feature = "vigenere")]
 pub mod vigenere;
 #[cfg(feature = "xor")]
 pub mod xor;
+
+use crate::Error;
+
+/// Common interface of all ciphers
+///
+/// Every cipher of this module implements this trait, so they can be stored
+/// as a `Box<dyn Cipher>` and swapped at runtime.
+///
+/// # Example:
+/// ```
+/// use cienli::ciphers::atbash::Atbash;
+/// use cienli::ciphers::caesar::Caesar;
+/// use cienli::ciphers::Cipher;
+///
+/// let ciphers: Vec<Box<dyn Cipher>> = vec![
+///     Box::new(Caesar::new(5).unwrap()),
+///     Box::new(Atbash::new()),
+/// ];
+///
+/// for cipher in ciphers.iter() {
+///     let cipher_text = cipher.encipher("Hello :)").unwrap();
+///     assert_eq!("Hello :)", cipher.decipher(&cipher_text).unwrap());
+/// }
+/// ```
+pub trait Cipher {
+    /// Enciphers a message with the cipher.
+    fn encipher(&self, message: &str) -> Result<String, Error>;
+
+    /// Deciphers a cipher with the cipher.
+    fn decipher(&self, cipher: &str) -> Result<String, Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(all(feature = "caesar", feature = "polybius_square"))]
+    fn swap_ciphers_test() {
+        use super::caesar::Caesar;
+        use super::polybius_square::PolybiusSquare;
+        use super::Cipher;
+
+        let mut cipher: Box<dyn Cipher> = Box::new(Caesar::new(5).unwrap());
+        assert_eq!("Mjqqt", cipher.encipher("Hello").unwrap());
+
+        cipher = Box::new(PolybiusSquare::new());
+        assert_eq!("2315313134", cipher.encipher("Hello").unwrap());
+        assert!(cipher.decipher("231").is_err());
+    }
+}
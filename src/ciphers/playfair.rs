@@ -0,0 +1,296 @@
+use crate::ciphers::Cipher;
+use crate::common::{KeySquare, LetterMerge};
+use crate::Error;
+
+/// Playfair Cipher
+///
+/// The struct is generated through the new() function.
+///
+pub struct Playfair {
+    square: KeySquare,
+    filler: char,
+    alternate_filler: char,
+}
+
+impl Playfair {
+    /// Initialize a playfair cipher with a keyword, 'J' is merged with 'I' and 'X' is the filler.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid keyword:
+    /// ```
+    /// use cienli::ciphers::playfair::Playfair;
+    /// let playfair = Playfair::new("Playfair Example");
+    ///
+    /// assert!(playfair.is_ok());
+    /// ```
+    ///
+    /// - Initialization with an invalid keyword:
+    /// ```
+    /// use cienli::ciphers::playfair::Playfair;
+    /// let playfair = Playfair::new("Playfair #1");
+    ///
+    /// assert!(playfair.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters and spaces.
+    pub fn new(keyword: &str) -> Result<Playfair, Error> {
+        Playfair::with_square(KeySquare::new(keyword, LetterMerge::IJ)?, 'X')
+    }
+
+    /// Initialize a playfair cipher with a square and the filler inserted between doubled
+    /// letters and after a message of odd length.
+    ///
+    /// A doubled filler is split by the letter that follows the filler in the alphabet.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::playfair::Playfair;
+    /// use cienli::common::{KeySquare, LetterMerge};
+    /// let square = KeySquare::new("Monarchy", LetterMerge::OmitQ).unwrap();
+    /// let playfair = Playfair::with_square(square, 'Z').unwrap();
+    ///
+    /// assert_eq!("IBTVVHNA", playfair.encipher("Balloon"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The filler and the letter that follows it must be in the square.
+    pub fn with_square(square: KeySquare, filler: char) -> Result<Playfair, Error> {
+        let filler = square
+            .normalize(filler)
+            .ok_or(Error::UnsupportedCharacter(filler))?;
+
+        let alternate_filler = ('A'..='Z')
+            .cycle()
+            .skip_while(|letter| *letter != filler)
+            .skip(1)
+            .take(26)
+            .find_map(|letter| square.normalize(letter).filter(|letter| *letter != filler))
+            .ok_or(Error::UnsupportedCharacter(filler))?;
+
+        Ok(Playfair {
+            square,
+            filler,
+            alternate_filler,
+        })
+    }
+
+    /// Enciphers a message with the playfair cipher.
+    ///
+    /// The characters outside of the square are removed, the letters are uppercased and
+    /// the filler splits the doubled letters of a pair and completes the last pair.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::playfair::Playfair;
+    /// let playfair = Playfair::new("Playfair Example").unwrap();
+    ///
+    /// assert_eq!(
+    ///     "BMODZBXDNABEKUDMUIXMMOUVIF",
+    ///     playfair.encipher("Hide the gold in the tree stump")
+    /// );
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let mut letters = message
+            .chars()
+            .filter_map(|character| self.square.normalize(character))
+            .peekable();
+
+        let mut pairs = Vec::new();
+        while let Some(first) = letters.next() {
+            let second = match letters.peek() {
+                Some(second) if *second != first => letters.next().unwrap_or(first),
+                _ => self.filler_for(first),
+            };
+            pairs.push((first, second));
+        }
+
+        pairs
+            .into_iter()
+            .flat_map(|(first, second)| {
+                let pair = self.transform_pair(first, second, 1);
+                [pair.0, pair.1]
+            })
+            .collect()
+    }
+
+    /// Deciphers a cipher with the playfair cipher.
+    ///
+    /// The spaces of the cipher are skipped, the fillers are kept in the plaintext.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::playfair::Playfair;
+    /// let playfair = Playfair::new("Playfair Example").unwrap();
+    ///
+    /// assert_eq!(
+    ///     "HIDETHEGOLDINTHETREXESTUMP",
+    ///     playfair.decipher("BMODZ BXDNA BEKUD MUIXM MOUVI F").unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher must be made of pairs of different letters of the square.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        let mut letters = Vec::new();
+
+        for (position, character) in cipher.chars().enumerate() {
+            if character.is_whitespace() {
+                continue;
+            }
+
+            match self.square.normalize(character) {
+                Some(letter) => letters.push((position, letter)),
+                None => return Err(Error::UnsupportedCharacter(character)),
+            }
+        }
+
+        let mut message = String::with_capacity(letters.len());
+        for pair in letters.chunks(2) {
+            match pair {
+                [(_, first), (_, second)] if first != second => {
+                    let (first, second) =
+                        self.transform_pair(*first, *second, self.square.size() - 1);
+                    message.push(first);
+                    message.push(second);
+                }
+                [.., (position, _)] => {
+                    return Err(Error::MalformedCiphertext {
+                        position: *position,
+                    })
+                }
+                [] => {}
+            }
+        }
+
+        Ok(message)
+    }
+
+    fn filler_for(&self, letter: char) -> char {
+        match letter == self.filler {
+            true => self.alternate_filler,
+            false => self.filler,
+        }
+    }
+
+    /// Transforms a pair of different letters, the shift is 1 to encipher and size - 1 to decipher.
+    fn transform_pair(&self, first: char, second: char, shift: usize) -> (char, char) {
+        let ((row1, column1), (row2, column2)) =
+            match (self.square.position(first), self.square.position(second)) {
+                (Some(first), Some(second)) => (first, second),
+                _ => return (first, second),
+            };
+
+        if row1 == row2 {
+            (
+                self.square.letter(row1, column1 + shift),
+                self.square.letter(row2, column2 + shift),
+            )
+        } else if column1 == column2 {
+            (
+                self.square.letter(row1 + shift, column1),
+                self.square.letter(row2 + shift, column2),
+            )
+        } else {
+            (
+                self.square.letter(row1, column2),
+                self.square.letter(row2, column1),
+            )
+        }
+    }
+}
+
+impl Cipher for Playfair {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Playfair::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Playfair::decipher(self, cipher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Playfair;
+    use crate::common::{KeySquare, LetterMerge};
+    use crate::Error;
+
+    #[test]
+    fn encipher_test() {
+        let playfair = Playfair::new("Playfair Example").unwrap();
+
+        assert_eq!(
+            "BMODZBXDNABEKUDMUIXMMOUVIF",
+            playfair.encipher("Hide the gold in the tree stump")
+        );
+    }
+
+    #[test]
+    fn decipher_test() {
+        let playfair = Playfair::new("Playfair Example").unwrap();
+
+        assert_eq!(
+            "HIDETHEGOLDINTHETREXESTUMP",
+            playfair.decipher("BMODZBXDNABEKUDMUIXMMOUVIF").unwrap()
+        );
+    }
+
+    #[test]
+    fn merged_letters_test() {
+        let playfair = Playfair::new("Jigsaw").unwrap();
+
+        assert_eq!(playfair.encipher("Jump"), playfair.encipher("Iump"));
+        assert_eq!(
+            "IUMP",
+            playfair.decipher(&playfair.encipher("Jump")).unwrap()
+        );
+    }
+
+    #[test]
+    fn doubled_filler_test() {
+        let playfair = Playfair::new("Keyword").unwrap();
+
+        assert_eq!("XYXY", playfair.decipher(&playfair.encipher("XX")).unwrap());
+        assert_eq!(
+            "AXXY",
+            playfair.decipher(&playfair.encipher("ax x")).unwrap()
+        );
+    }
+
+    #[test]
+    fn omit_q_test() {
+        let square = KeySquare::new("Secret", LetterMerge::OmitQ).unwrap();
+        let playfair = Playfair::with_square(square, 'X').unwrap();
+
+        assert_eq!(
+            "UICKLY",
+            playfair.decipher(&playfair.encipher("Quickly")).unwrap()
+        );
+        assert_eq!(
+            Err(Error::UnsupportedCharacter('Q')),
+            playfair.decipher("QA")
+        );
+    }
+
+    #[test]
+    fn invalid_filler_test() {
+        let square = KeySquare::new("Secret", LetterMerge::OmitQ).unwrap();
+
+        assert!(Playfair::with_square(square, 'Q').is_err());
+    }
+
+    #[test]
+    fn malformed_cipher_test() {
+        let playfair = Playfair::new("Playfair Example").unwrap();
+
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 1 }),
+            playfair.decipher("BB")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 2 }),
+            playfair.decipher("BMO")
+        );
+    }
+}
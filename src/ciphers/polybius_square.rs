@@ -1,39 +1,85 @@
-const TABLE: [[char; 5]; 5] = [
-    ['A', 'B', 'C', 'D', 'E'],
-    ['F', 'G', 'H', 'J', 'K'],
-    ['L', 'M', 'N', 'O', 'P'],
-    ['Q', 'R', 'S', 'T', 'U'],
-    ['V', 'W', 'X', 'Y', 'Z'],
-]; // Removed letter: I
+use crate::ciphers::Cipher;
+use crate::common::{KeySquare, LetterMerge};
+use crate::Error;
 
 /// Polybius square cipher
 ///
 /// The struct is generated through the new() function
 ///
-pub struct PolybiusSquare<'a> {
-    message: &'a str,
+pub struct PolybiusSquare {
+    square: KeySquare,
+    labels: Vec<char>,
+    preserve: bool,
 }
 
-impl PolybiusSquare<'_> {
-    /// Initialize a polybius square cipher with a cipher/plain text
+impl PolybiusSquare {
+    /// Initialize a polybius square cipher with the alphabetical 5x5 square, 'J' merged
+    /// with 'I' and the rows and columns labeled from 1 to 5.
     ///
     /// # Examples:
-    /// - Initialization with a plaintext:
     /// ```
     /// use cienli::ciphers::polybius_square::PolybiusSquare;
     ///
-    /// let polybius = PolybiusSquare::new("Hello World :)");
+    /// let polybius = PolybiusSquare::new();
     /// ```
     ///
-    /// - Initialization with a ciphertext:
+    pub fn new() -> PolybiusSquare {
+        PolybiusSquare {
+            square: KeySquare::new("", LetterMerge::IJ).expect("The empty keyword is valid"),
+            labels: vec!['1', '2', '3', '4', '5'],
+            preserve: false,
+        }
+    }
+
+    /// Initialize a polybius square cipher with a square, the labels of its rows and
+    /// columns, and whether the characters outside of the square are preserved or removed.
+    ///
+    /// The preserved characters that are also labels are removed.
+    ///
+    /// # Examples:
+    /// - A keyword square with letter labels:
     /// ```
     /// use cienli::ciphers::polybius_square::PolybiusSquare;
+    /// use cienli::common::{KeySquare, LetterMerge};
     ///
-    /// let polybius = PolybiusSquare::new("23153131345234423114");
+    /// let square = KeySquare::new("Zebras", LetterMerge::CK).unwrap();
+    /// let polybius = PolybiusSquare::with_square(square, "ADFGX", false).unwrap();
+    /// assert_eq!("FAADFGFGGD", polybius.encipher("Hello"));
     /// ```
     ///
-    pub fn new(message: &str) -> PolybiusSquare {
-        PolybiusSquare { message }
+    /// - A 6x6 alphanumeric square preserving the spaces and the punctuation:
+    /// ```
+    /// use cienli::ciphers::polybius_square::PolybiusSquare;
+    /// use cienli::common::KeySquare;
+    ///
+    /// let square = KeySquare::alphanumeric("").unwrap();
+    /// let polybius = PolybiusSquare::with_square(square, "123456", true).unwrap();
+    /// assert_eq!("2223 5566!", polybius.encipher("Hi 29!"));
+    /// ```
+    ///
+    /// # Errors:
+    /// There must be one label per row of the square and the labels must be different.
+    pub fn with_square(
+        square: KeySquare,
+        labels: &str,
+        preserve: bool,
+    ) -> Result<PolybiusSquare, Error> {
+        let labels = labels.chars().collect::<Vec<char>>();
+
+        if labels.len() != square.size() {
+            return Err(Error::InvalidKey(
+                "There must be one label per row of the square",
+            ));
+        }
+        if (1..labels.len()).any(|index| labels[..index].contains(&labels[index])) {
+            return Err(Error::InvalidKey("The labels must be different"));
+        }
+
+        Ok(PolybiusSquare {
+            square,
+            labels,
+            preserve,
+        })
     }
 
     /// Enciphers a message with the polybius square cipher:
@@ -42,34 +88,26 @@ impl PolybiusSquare<'_> {
     /// ```
     /// use cienli::ciphers::polybius_square::PolybiusSquare;
     ///
-    /// let polybius = PolybiusSquare::new("Hello World :)");
-    /// assert_eq!("23153131345234423114", polybius.encipher());
+    /// let polybius = PolybiusSquare::new();
+    /// assert_eq!("23153131345234423114", polybius.encipher("Hello World :)"));
     /// ```
-    pub fn encipher(&self) -> String {
-        self.message
-            .to_ascii_uppercase()
-            .chars()
-            .map(|character| match character {
-                'A'..='Z' => {
-                    let mut row = ((character as u8 - 65) / 5) + 1;
-                    let mut col = ((character as u8 - 65) % 5) + 1;
-
-                    if character == 'K' {
-                        row -= 1;
-                        col = 5 - col + 1;
-                    } else if character >= 'J' {
-                        if col == 1 {
-                            col = 6;
-                            row -= 1;
-                        }
-                        col -= 1;
-                    }
-
-                    format!("{}{}", row, col)
+    pub fn encipher(&self, message: &str) -> String {
+        let mut result = String::with_capacity(message.len() * 2);
+
+        for character in message.chars() {
+            match self.square.position(character) {
+                Some((row, column)) => {
+                    result.push(self.labels[row]);
+                    result.push(self.labels[column]);
+                }
+                None if self.preserve && !self.labels.contains(&character) => {
+                    result.push(character)
                 }
-                _ => String::from(""),
-            })
-            .collect()
+                None => {}
+            }
+        }
+
+        result
     }
 
     /// Deciphers a ciphertext with the polybius square cipher:
@@ -78,66 +116,151 @@ impl PolybiusSquare<'_> {
     /// ```
     /// use cienli::ciphers::polybius_square::PolybiusSquare;
     ///
-    /// let polybius = PolybiusSquare::new("23153131345234423114");
-    /// assert_eq!("HELLOWORLD", polybius.decipher().unwrap());
+    /// let polybius = PolybiusSquare::new();
+    /// assert_eq!("HELLOWORLD", polybius.decipher("23153131345234423114").unwrap());
     /// ```
     ///
-    /// # Error:
-    /// If you try to decipher a non-numeric text you will get an error.
-    pub fn decipher(&self) -> Result<String, &'static str> {
-        if self.message.len() % 2 != 0 {
-            return Err("1 column is missing");
-        }
-        if !PolybiusSquare::is_string_numeric(self.message) {
-            return Err("Ciphertext must be numeric");
-        }
-
+    /// # Errors:
+    /// The ciphertext must be made of pairs of labels, the other characters are only
+    /// allowed between the pairs when they are preserved.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
         let mut result = String::new();
+        let mut row = None;
+        let mut length = 0;
 
-        let cipher_len = self.message.len();
-        for i in 1..(cipher_len / 2) + 1 {
-            let row_and_col = &self.message.as_bytes()[(i * 2) - 2..i * 2];
-            let row: usize = (row_and_col[0] as char)
-                .to_string()
-                .parse::<usize>()
-                .unwrap()
-                - 1;
-            let col: usize = (row_and_col[1] as char)
-                .to_string()
-                .parse::<usize>()
-                .unwrap()
-                - 1;
-            result.push(TABLE[row][col]);
+        for (position, character) in cipher.chars().enumerate() {
+            length = position + 1;
+
+            match (
+                self.labels.iter().position(|label| *label == character),
+                row,
+            ) {
+                (Some(label), None) => row = Some(label),
+                (Some(column), Some(label)) => {
+                    result.push(self.square.letter(label, column));
+                    row = None;
+                }
+                (None, None) if self.preserve => result.push(character),
+                _ => return Err(Error::MalformedCiphertext { position }),
+            }
         }
 
-        Ok(result)
+        match row {
+            Some(_) => Err(Error::MalformedCiphertext { position: length }),
+            None => Ok(result),
+        }
     }
+}
 
-    fn is_string_numeric(text: &str) -> bool {
-        for character in text.chars() {
-            if !character.is_numeric() {
-                return false;
-            }
-        }
-        true
+impl Default for PolybiusSquare {
+    fn default() -> Self {
+        PolybiusSquare::new()
+    }
+}
+
+impl Cipher for PolybiusSquare {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(PolybiusSquare::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        PolybiusSquare::decipher(self, cipher)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::PolybiusSquare;
+    use crate::common::{KeySquare, LetterMerge};
+    use crate::Error;
 
     #[test]
     fn encipher_test() {
-        let polybius = PolybiusSquare::new("Hello World :)");
+        let polybius = PolybiusSquare::new();
 
-        assert_eq!("23153131345234423114", polybius.encipher());
+        assert_eq!("23153131345234423114", polybius.encipher("Hello World :)"));
     }
 
     #[test]
     fn decipher_test() {
-        let polybius = PolybiusSquare::new("23153131345234423114");
+        let polybius = PolybiusSquare::new();
+
+        assert_eq!(
+            "HELLOWORLD",
+            polybius.decipher("23153131345234423114").unwrap()
+        );
+    }
+
+    #[test]
+    fn decipher_malformed_test() {
+        let polybius = PolybiusSquare::new();
+
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 5 }),
+            polybius.decipher("23153")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 3 }),
+            polybius.decipher("2316")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 0 }),
+            polybius.decipher("A1")
+        );
+    }
+
+    #[test]
+    fn merged_letters_test() {
+        let polybius = PolybiusSquare::new();
+
+        assert_eq!("24", polybius.encipher("J"));
+        assert_eq!("I", polybius.decipher("24").unwrap());
+    }
+
+    #[test]
+    fn keyword_square_test() {
+        let square = KeySquare::new("Polybius", LetterMerge::IJ).unwrap();
+        let polybius = PolybiusSquare::with_square(square, "12345", false).unwrap();
+
+        assert_eq!("11", polybius.encipher("P"));
+        assert_eq!(
+            "ATTACK",
+            polybius.decipher(&polybius.encipher("Attack")).unwrap()
+        );
+    }
+
+    #[test]
+    fn merge_c_k_test() {
+        let square = KeySquare::new("", LetterMerge::CK).unwrap();
+        let polybius = PolybiusSquare::with_square(square, "12345", false).unwrap();
+
+        assert_eq!(polybius.encipher("C"), polybius.encipher("K"));
+        assert_eq!(
+            "IJCE",
+            polybius.decipher(&polybius.encipher("Ijke")).unwrap()
+        );
+    }
+
+    #[test]
+    fn preserve_test() {
+        let square = KeySquare::new("", LetterMerge::IJ).unwrap();
+        let polybius = PolybiusSquare::with_square(square, "ABCDE", true).unwrap();
+        let cipher = polybius.encipher("Hello, World!");
+
+        assert_eq!("BCAECACACD, EBCDDBCAAD!", cipher);
+        assert_eq!("HELLO, WORLD!", polybius.decipher(&cipher).unwrap());
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 1 }),
+            polybius.decipher("B CA")
+        );
+    }
+
+    #[test]
+    fn invalid_labels_test() {
+        let square = KeySquare::alphanumeric("").unwrap();
 
-        assert_eq!("HELLOWORLD", polybius.decipher().unwrap());
+        assert!(PolybiusSquare::with_square(square.clone(), "12345", false).is_err());
+        assert!(PolybiusSquare::with_square(square.clone(), "ADFGVA", false).is_err());
+        assert!(PolybiusSquare::with_square(square, "ADFGVX", false).is_ok());
     }
 }
@@ -0,0 +1,183 @@
+use crate::ciphers::Cipher;
+use crate::Error;
+
+/// Rail Fence Cipher
+///
+/// The message is written in a zigzag on the rails, then the rails are read one after another.
+///
+/// The struct is generated through the new() function.
+///
+pub struct RailFence {
+    rails: usize,
+    offset: usize,
+}
+
+impl RailFence {
+    /// Initialize a rail fence cipher with the number of rails.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid number of rails:
+    /// ```
+    /// use cienli::ciphers::rail_fence::RailFence;
+    /// let rail_fence = RailFence::new(3);
+    ///
+    /// assert!(rail_fence.is_ok());
+    /// ```
+    ///
+    /// - Initialization without rails:
+    /// ```
+    /// use cienli::ciphers::rail_fence::RailFence;
+    /// let rail_fence = RailFence::new(0);
+    ///
+    /// assert!(rail_fence.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The number of rails must not be zero.
+    pub fn new(rails: usize) -> Result<RailFence, Error> {
+        RailFence::with_offset(rails, 0)
+    }
+
+    /// Initialize a rail fence cipher with the number of rails and the offset, the number
+    /// of steps of the zigzag skipped before the first character.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::rail_fence::RailFence;
+    /// let rail_fence = RailFence::with_offset(3, 2).unwrap();
+    ///
+    /// assert_eq!("AIVDENERDSOEEFEAOCWECRLTE", rail_fence.encipher("WEAREDISCOVEREDFLEEATONCE"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The number of rails must not be zero and the offset must be lower than the length
+    /// of a zigzag (twice the number of rails minus two).
+    pub fn with_offset(rails: usize, offset: usize) -> Result<RailFence, Error> {
+        if rails == 0 {
+            return Err(Error::InvalidKey("Number of rails cannot be zero"));
+        }
+
+        if offset >= RailFence::cycle(rails) {
+            return Err(Error::InvalidKey(
+                "Offset must be lower than the length of a zigzag",
+            ));
+        }
+
+        Ok(RailFence { rails, offset })
+    }
+
+    /// Enciphers a message with the rail fence cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::rail_fence::RailFence;
+    /// let rail_fence = RailFence::new(3).unwrap();
+    ///
+    /// assert_eq!("WECRLTEERDSOEEFEAOCAIVDEN", rail_fence.encipher("WEAREDISCOVEREDFLEEATONCE"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let characters = message.chars().collect::<Vec<char>>();
+
+        self.order(characters.len())
+            .into_iter()
+            .map(|position| characters[position])
+            .collect()
+    }
+
+    /// Deciphers a cipher with the rail fence cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::rail_fence::RailFence;
+    /// let rail_fence = RailFence::new(3).unwrap();
+    ///
+    /// assert_eq!("WEAREDISCOVEREDFLEEATONCE", rail_fence.decipher("WECRLTEERDSOEEFEAOCAIVDEN"));
+    /// ```
+    pub fn decipher(&self, cipher: &str) -> String {
+        let characters = cipher.chars().collect::<Vec<char>>();
+        let mut message = vec!['\0'; characters.len()];
+
+        for (character, position) in characters.iter().zip(self.order(characters.len())) {
+            message[position] = *character;
+        }
+
+        message.into_iter().collect()
+    }
+
+    /// Returns the positions of the characters of a message in the order they are read.
+    fn order(&self, length: usize) -> Vec<usize> {
+        let cycle = RailFence::cycle(self.rails);
+        let rail = |position: usize| {
+            let step = (position + self.offset) % cycle;
+            step.min(cycle - step)
+        };
+
+        let mut order = (0..length).collect::<Vec<usize>>();
+        order.sort_by_key(|position| rail(*position));
+        order
+    }
+
+    fn cycle(rails: usize) -> usize {
+        (2 * rails).saturating_sub(2).max(1)
+    }
+}
+
+impl Cipher for RailFence {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(RailFence::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(RailFence::decipher(self, cipher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RailFence;
+
+    #[test]
+    fn encipher_test() {
+        let rail_fence = RailFence::new(3).unwrap();
+
+        assert_eq!(
+            "WECRLTEERDSOEEFEAOCAIVDEN",
+            rail_fence.encipher("WEAREDISCOVEREDFLEEATONCE")
+        );
+    }
+
+    #[test]
+    fn decipher_test() {
+        let rail_fence = RailFence::new(3).unwrap();
+
+        assert_eq!(
+            "WEAREDISCOVEREDFLEEATONCE",
+            rail_fence.decipher("WECRLTEERDSOEEFEAOCAIVDEN")
+        );
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let message = "Hello, World! • Привет";
+
+        for rails in 1..10 {
+            for offset in 0..(2 * rails - 2).max(1) {
+                let rail_fence = RailFence::with_offset(rails, offset).unwrap();
+                assert_eq!(message, rail_fence.decipher(&rail_fence.encipher(message)));
+            }
+        }
+    }
+
+    #[test]
+    fn single_rail_test() {
+        let rail_fence = RailFence::new(1).unwrap();
+        assert_eq!("Hello :)", rail_fence.encipher("Hello :)"));
+    }
+
+    #[test]
+    fn invalid_test() {
+        assert!(RailFence::new(0).is_err());
+        assert!(RailFence::with_offset(3, 4).is_err());
+        assert!(RailFence::with_offset(1, 1).is_err());
+    }
+}
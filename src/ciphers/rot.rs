@@ -1,3 +1,10 @@
+#[cfg(feature = "analysis")]
+use crate::analysis::{english_score, rank, Candidate};
+use crate::ciphers::Cipher;
+use crate::stream::{transform_chars, Direction, StreamCipher};
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RotType {
     Rot5,
     Rot13,
@@ -9,22 +16,21 @@ pub enum RotType {
 ///
 /// The struct is generated through the new() function.
 ///
-pub struct Rot<'a> {
-    message: &'a str,
+pub struct Rot {
     rot_type: RotType,
 }
 
-impl Rot<'_> {
-    /// Initialize a rot cipher with a message and rot type.
+impl Rot {
+    /// Initialize a rot cipher with a rot type.
     ///
     /// # Examples:
     /// - Initialization with Rot13 type.:
     /// ```
     /// use cienli::ciphers::rot::{Rot, RotType};
-    /// let rot = Rot::new("• Hello Friend 83110 :) •", RotType::Rot13);
+    /// let rot = Rot::new(RotType::Rot13);
     /// ```
-    pub fn new(message: &str, rot_type: RotType) -> Rot {
-        Rot { message, rot_type }
+    pub fn new(rot_type: RotType) -> Rot {
+        Rot { rot_type }
     }
 
     /// Enciphers a message with the rot cipher.
@@ -34,25 +40,23 @@ impl Rot<'_> {
     /// - Encipher with Rot47:
     /// ```
     /// use cienli::ciphers::rot::{Rot, RotType};
-    /// let rot47 = Rot::new("• Hello Friend 83110 :) •", RotType::Rot47);
+    /// let rot47 = Rot::new(RotType::Rot47);
     ///
-    /// assert_eq!("• w6==@ uC:6?5 gb``_ iX •", rot47.encipher());
+    /// assert_eq!("• w6==@ uC:6?5 gb``_ iX •", rot47.encipher("• Hello Friend 83110 :) •"));
     /// ```
     ///
     /// - Encipher with Rot13:
     /// ```
     /// use cienli::ciphers::rot::{Rot, RotType};
-    /// let rot13 = Rot::new("• Hello Friend 83110 :) •", RotType::Rot13);
+    /// let rot13 = Rot::new(RotType::Rot13);
     ///
-    /// assert_eq!("• Uryyb Sevraq 83110 :) •", rot13.encipher());
+    /// assert_eq!("• Uryyb Sevraq 83110 :) •", rot13.encipher("• Hello Friend 83110 :) •"));
     /// ```
-    pub fn encipher(&self) -> String {
-        match self.rot_type {
-            RotType::Rot5 => Rot::rot5(self.message),
-            RotType::Rot13 => Rot::rot13(self.message),
-            RotType::Rot18 => Rot::rot13(&(Rot::rot5(self.message))),
-            RotType::Rot47 => Rot::rot47(self.message),
-        }
+    pub fn encipher(&self, message: &str) -> String {
+        message
+            .chars()
+            .map(|character| self.rotate(character))
+            .collect()
     }
 
     /// Deciphers a cipher with the rot cipher.
@@ -62,53 +66,112 @@ impl Rot<'_> {
     /// - Decipher with Rot47:
     /// ```
     /// use cienli::ciphers::rot::{Rot, RotType};
-    /// let rot47 = Rot::new("• w6==@ uC:6?5 gb``_ iX •", RotType::Rot47);
+    /// let rot47 = Rot::new(RotType::Rot47);
     ///
-    /// assert_eq!("• Hello Friend 83110 :) •", rot47.decipher());
+    /// assert_eq!("• Hello Friend 83110 :) •", rot47.decipher("• w6==@ uC:6?5 gb``_ iX •"));
     /// ```
     ///
     /// - Decipher with Rot13:
     /// ```
     /// use cienli::ciphers::rot::{Rot, RotType};
-    /// let rot13 = Rot::new("• Uryyb Sevraq 83110 :) •", RotType::Rot13);
+    /// let rot13 = Rot::new(RotType::Rot13);
     ///
-    /// assert_eq!("• Hello Friend 83110 :) •", rot13.decipher());
+    /// assert_eq!("• Hello Friend 83110 :) •", rot13.decipher("• Uryyb Sevraq 83110 :) •"));
     /// ```
-    pub fn decipher(&self) -> String {
-        self.encipher()
+    pub fn decipher(&self, cipher: &str) -> String {
+        self.encipher(cipher)
     }
 
-    fn rot5(message: &str) -> String {
-        message
-            .chars()
-            .map(|digit| match digit {
-                '0'..='4' => ((digit as u8) + 5) as char,
-                '5'..='9' => ((digit as u8) - 5) as char,
-                _ => digit,
+    /// Tries every rotation of the printable ASCII characters (the alphabet of Rot47)
+    /// and returns the candidates ranked from the most likely english text to the least likely.
+    ///
+    /// The key of a candidate is the rotation that was used to encipher it, 47 for Rot47.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::rot::Rot;
+    /// let candidates = Rot::crack_rot47("• w6==@ uC:6?5 gb``_ iX •");
+    ///
+    /// assert_eq!(47, candidates[0].key);
+    /// assert_eq!("• Hello Friend 83110 :) •", candidates[0].plaintext);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack_rot47(cipher: &str) -> Vec<Candidate<u8>> {
+        let mut candidates = (1..=94)
+            .map(|rotation| {
+                let plaintext = cipher
+                    .chars()
+                    .map(|character| match character {
+                        '!'..='~' => (((character as u8 - 33) + 94 - rotation) % 94 + 33) as char,
+                        _ => character,
+                    })
+                    .collect::<String>();
+
+                Candidate {
+                    key: rotation,
+                    score: english_score(&plaintext),
+                    plaintext,
+                }
             })
-            .collect()
+            .collect::<Vec<Candidate<u8>>>();
+
+        rank(&mut candidates);
+        candidates
     }
 
-    fn rot13(message: &str) -> String {
-        message
-            .chars()
-            .map(|character| match character {
-                'A'..='M' | 'a'..='m' => ((character as u8) + 13) as char,
-                'N'..='Z' | 'n'..='z' => ((character as u8) - 13) as char,
-                _ => character,
-            })
-            .collect()
+    fn rotate(&self, character: char) -> char {
+        match self.rot_type {
+            RotType::Rot5 => Rot::rot5(character),
+            RotType::Rot13 => Rot::rot13(character),
+            RotType::Rot18 => Rot::rot13(Rot::rot5(character)),
+            RotType::Rot47 => Rot::rot47(character),
+        }
     }
 
-    fn rot47(message: &str) -> String {
-        message
-            .chars()
-            .map(|character| match character {
-                '!'..='O' => ((character as u8) + 47) as char,
-                'P'..='~' => ((character as u8) - 47) as char,
-                _ => character,
-            })
-            .collect()
+    fn rot5(digit: char) -> char {
+        match digit {
+            '0'..='4' => ((digit as u8) + 5) as char,
+            '5'..='9' => ((digit as u8) - 5) as char,
+            _ => digit,
+        }
+    }
+
+    fn rot13(character: char) -> char {
+        match character {
+            'A'..='M' | 'a'..='m' => ((character as u8) + 13) as char,
+            'N'..='Z' | 'n'..='z' => ((character as u8) - 13) as char,
+            _ => character,
+        }
+    }
+
+    fn rot47(character: char) -> char {
+        match character {
+            '!'..='O' => ((character as u8) + 47) as char,
+            'P'..='~' => ((character as u8) - 47) as char,
+            _ => character,
+        }
+    }
+}
+
+impl Cipher for Rot {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Rot::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Rot::decipher(self, cipher))
+    }
+}
+
+impl StreamCipher for Rot {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        _position: &mut usize,
+        _direction: Direction,
+    ) -> usize {
+        transform_chars(input, output, |character| self.rotate(character))
     }
 }
 
@@ -118,57 +181,96 @@ mod tests {
 
     #[test]
     fn rot47_encipher() {
-        let rot47 = Rot::new("• Hello Friend 83110 :) •", RotType::Rot47);
+        let rot47 = Rot::new(RotType::Rot47);
+
+        assert_eq!(
+            "• w6==@ uC:6?5 gb``_ iX •",
+            rot47.encipher("• Hello Friend 83110 :) •")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn rot47_crack() {
+        let cipher =
+            Rot::new(RotType::Rot47).encipher("Meet me at the usual place, 10 o'clock sharp!");
+        let candidates = Rot::crack_rot47(&cipher);
 
-        assert_eq!("• w6==@ uC:6?5 gb``_ iX •", rot47.encipher());
+        assert_eq!(94, candidates.len());
+        assert_eq!(47, candidates[0].key);
+        assert_eq!(
+            "Meet me at the usual place, 10 o'clock sharp!",
+            candidates[0].plaintext
+        );
     }
 
     #[test]
     fn rot47_decipher() {
-        let rot47 = Rot::new("• w6==@ uC:6?5 gb``_ iX •", RotType::Rot47);
+        let rot47 = Rot::new(RotType::Rot47);
 
-        assert_eq!("• Hello Friend 83110 :) •", rot47.decipher());
+        assert_eq!(
+            "• Hello Friend 83110 :) •",
+            rot47.decipher("• w6==@ uC:6?5 gb``_ iX •")
+        );
     }
 
     #[test]
     fn rot18_encipher() {
-        let rot18 = Rot::new("• Hello Friend 83110 :) •", RotType::Rot18);
+        let rot18 = Rot::new(RotType::Rot18);
 
-        assert_eq!("• Uryyb Sevraq 38665 :) •", rot18.encipher());
+        assert_eq!(
+            "• Uryyb Sevraq 38665 :) •",
+            rot18.encipher("• Hello Friend 83110 :) •")
+        );
     }
 
     #[test]
     fn rot18_decipher() {
-        let rot18 = Rot::new("• Uryyb Sevraq 38665 :) •", RotType::Rot18);
+        let rot18 = Rot::new(RotType::Rot18);
 
-        assert_eq!("• Hello Friend 83110 :) •", rot18.decipher());
+        assert_eq!(
+            "• Hello Friend 83110 :) •",
+            rot18.decipher("• Uryyb Sevraq 38665 :) •")
+        );
     }
 
     #[test]
     fn rot13_encipher() {
-        let rot13 = Rot::new("• Hello Friend 83110 :) •", RotType::Rot13);
+        let rot13 = Rot::new(RotType::Rot13);
 
-        assert_eq!("• Uryyb Sevraq 83110 :) •", rot13.encipher());
+        assert_eq!(
+            "• Uryyb Sevraq 83110 :) •",
+            rot13.encipher("• Hello Friend 83110 :) •")
+        );
     }
 
     #[test]
     fn rot13_decipher() {
-        let rot13 = Rot::new("• Uryyb Sevraq 83110 :) •", RotType::Rot13);
+        let rot13 = Rot::new(RotType::Rot13);
 
-        assert_eq!("• Hello Friend 83110 :) •", rot13.decipher());
+        assert_eq!(
+            "• Hello Friend 83110 :) •",
+            rot13.decipher("• Uryyb Sevraq 83110 :) •")
+        );
     }
 
     #[test]
     fn rot5_encipher() {
-        let rot5 = Rot::new("• Hello Friend 83110 :) •", RotType::Rot5);
+        let rot5 = Rot::new(RotType::Rot5);
 
-        assert_eq!("• Hello Friend 38665 :) •", rot5.encipher());
+        assert_eq!(
+            "• Hello Friend 38665 :) •",
+            rot5.encipher("• Hello Friend 83110 :) •")
+        );
     }
 
     #[test]
     fn rot5_decipher() {
-        let rot5 = Rot::new("• Hello Friend 38665 :) •", RotType::Rot5);
+        let rot5 = Rot::new(RotType::Rot5);
 
-        assert_eq!("• Hello Friend 83110 :) •", rot5.decipher());
+        assert_eq!(
+            "• Hello Friend 83110 :) •",
+            rot5.decipher("• Hello Friend 38665 :) •")
+        );
     }
 }
@@ -1,3 +1,8 @@
+#[cfg(feature = "analysis")]
+use crate::analysis::{rank, Candidate, NgramModel};
+use crate::ciphers::Cipher;
+use crate::Error;
+
 /// Scytale Cipher
 ///
 /// the struct is generated through the new() function.
@@ -24,9 +29,9 @@ impl Scytale {
     ///
     /// assert!(scytale.is_err());
     /// ```
-    pub fn new(key: usize) -> Result<Scytale, &'static str> {
+    pub fn new(key: usize) -> Result<Scytale, Error> {
         match key {
-            0 => Err("Key cannot be zero"),
+            0 => Err(Error::InvalidKey("Key cannot be zero")),
             _ => Ok(Scytale { key }),
         }
     }
@@ -45,7 +50,7 @@ impl Scytale {
             return message.to_string();
         }
 
-        let table = Scytale::generate_table(self.key, message, false);
+        let table = Scytale::generate_table(self.key, message);
 
         table
             .iter()
@@ -69,44 +74,118 @@ impl Scytale {
             return cipher.to_string();
         }
 
-        let mut table = Scytale::generate_table(self.key, cipher, true);
+        // The padding is dropped, so a cipher whose padding was lost deciphers the same way.
+        let characters = cipher
+            .chars()
+            .filter(|character| *character != '\0')
+            .collect::<Vec<char>>();
+        if characters.is_empty() {
+            return String::new();
+        }
+
+        let width = (characters.len() + self.key - 1) / self.key;
+        let full_rows = characters.len() - (width - 1) * self.key;
 
-        let mut message = String::new();
-        while table
-            .iter()
-            .filter(|character| !character.is_empty())
-            .count()
-            > 0
-        {
-            for column in table.iter_mut() {
-                message.push(column.remove(0));
-            }
+        let mut rows = Vec::with_capacity(self.key);
+        let mut start = 0;
+        for row in 0..self.key {
+            let length = if row < full_rows { width } else { width - 1 };
+            rows.push(&characters[start..start + length]);
+            start += length;
         }
-        message.trim_end_matches('\0').to_string()
+
+        (0..width)
+            .flat_map(|column| rows.iter().filter_map(move |row| row.get(column)))
+            .collect()
     }
 
-    fn generate_table(height: usize, message: &str, decipher: bool) -> Vec<Vec<char>> {
+    /// Tries every key up to the length of the cipher and returns the candidates ranked
+    /// by the english bigram and quadgram statistics of the plaintexts.
+    ///
+    /// The padding ('\0') kept in the cipher by encipher() rules out the keys that do not
+    /// encipher the candidate back to the cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::scytale::Scytale;
+    /// let scytale = Scytale::new(4).unwrap();
+    /// let cipher = scytale.encipher("Send more troops to the northern pass");
+    ///
+    /// let candidates = Scytale::crack(&cipher);
+    /// assert_eq!(4, candidates[0].key);
+    /// assert_eq!("Send more troops to the northern pass", candidates[0].plaintext);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack(cipher: &str) -> Vec<Candidate<usize>> {
+        let bigrams = NgramModel::english_bigrams();
+        let quadgrams = NgramModel::english_quadgrams();
+
+        Scytale::crack_with(cipher, |text| bigrams.score(text) + quadgrams.score(text))
+    }
+
+    /// Tries every key up to the length of the cipher and returns the candidates ranked
+    /// by a scoring function, the higher the score the more likely the plaintext.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    /// use cienli::ciphers::scytale::Scytale;
+    /// let model = NgramModel::english_bigrams();
+    ///
+    /// let candidates = Scytale::crack_with("Wthtees alac h atwl", |text| model.score(text));
+    /// assert_eq!("Watch the east wall", candidates[0].plaintext);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack_with<F>(cipher: &str, score: F) -> Vec<Candidate<usize>>
+    where
+        F: Fn(&str) -> f64,
+    {
+        let padded = cipher.contains('\0');
+
+        let mut candidates = (1..cipher.chars().count().max(2))
+            .filter_map(|key| Scytale::new(key).ok())
+            .map(|scytale| (scytale.key, scytale.decipher(cipher), scytale))
+            .filter(|(_, plaintext, scytale)| !padded || scytale.encipher(plaintext) == cipher)
+            .map(|(key, plaintext, _)| Candidate {
+                key,
+                score: score(&plaintext),
+                plaintext,
+            })
+            .collect::<Vec<Candidate<usize>>>();
+
+        rank(&mut candidates);
+        candidates
+    }
+
+    fn generate_table(height: usize, message: &str) -> Vec<Vec<char>> {
         let width = (message.chars().count() as f32 / height as f32).ceil() as usize;
 
         let mut table = vec![vec!['\0'; width]; height];
 
         for (position, element) in message.chars().enumerate() {
-            let (column, row) = match decipher {
-                true => (position / height, position % height),
-                false => (position % height, position / height),
-            };
-
-            table[column][row] = element;
+            table[position % height][position / height] = element;
         }
 
         table
     }
 }
 
+impl Cipher for Scytale {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Scytale::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Scytale::decipher(self, cipher))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Scytale;
 
+    const MESSAGE: &str = "The quick brown fox jumps";
+
     #[test]
     fn invalid_key_test() {
         assert!(Scytale::new(0).is_err());
@@ -135,4 +214,49 @@ mod tests {
         let scytale = Scytale::new(3).unwrap();
         assert_eq!("Hello :)", scytale.decipher("Hl:eo)l "));
     }
+
+    #[test]
+    fn round_trip_test() {
+        for key in 1..=30 {
+            let scytale = Scytale::new(key).unwrap();
+            assert_eq!(MESSAGE, scytale.decipher(&scytale.encipher(MESSAGE)));
+        }
+    }
+
+    #[test]
+    fn only_padding_test() {
+        let scytale = Scytale::new(2).unwrap();
+        assert_eq!("", scytale.decipher("\0\0\0"));
+    }
+
+    #[test]
+    fn lost_padding_test() {
+        let scytale = Scytale::new(6).unwrap();
+        let cipher = scytale.encipher(MESSAGE).replace('\0', "");
+
+        assert_eq!(MESSAGE, scytale.decipher(&cipher));
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_test() {
+        let message = "Meet me by the old oak tree at midnight and bring the map";
+
+        for key in [3, 7, 11] {
+            let scytale = Scytale::new(key).unwrap();
+            let candidates = Scytale::crack(&scytale.encipher(message));
+
+            assert_eq!(key, candidates[0].key);
+            assert_eq!(message, candidates[0].plaintext);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_lost_padding_test() {
+        let scytale = Scytale::new(6).unwrap();
+        let cipher = scytale.encipher(MESSAGE).replace('\0', "");
+
+        assert_eq!(MESSAGE, Scytale::crack(&cipher)[0].plaintext);
+    }
 }
@@ -0,0 +1,585 @@
+#[cfg(feature = "analysis")]
+use crate::analysis::{letters, Candidate, NgramModel};
+use crate::ciphers::Cipher;
+use crate::common::{Alphabet, Case};
+use crate::stream::{transform_chars, Direction, StreamCipher};
+use crate::Error;
+
+/// Where the keyword is written in a keyword mixed alphabet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordPlacement {
+    /// The keyword is followed by the other letters in alphabetical order.
+    Start,
+    /// The other letters in alphabetical order are followed by the keyword.
+    End,
+    /// The keyword starts at this position and the other letters wrap around.
+    Offset(usize),
+    /// The keyword is followed by the other letters in alphabetical order, starting after
+    /// the last letter of the keyword and wrapping around.
+    AfterLastLetter,
+}
+
+/// The settings of [`Substitution::crack_with`]
+///
+/// Every restart anneals a random key for the given number of iterations, each iteration
+/// swapping two letters of the key. The swaps making the plaintext worse are accepted less
+/// and less often as the temperature cools down. The same seed always gives the same result,
+/// without a seed the random keys are seeded from the clock.
+#[cfg(feature = "analysis")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrackOptions {
+    pub seed: Option<u64>,
+    pub restarts: usize,
+    pub iterations: usize,
+}
+
+#[cfg(feature = "analysis")]
+impl Default for CrackOptions {
+    fn default() -> Self {
+        CrackOptions {
+            seed: None,
+            restarts: 5,
+            iterations: 20000,
+        }
+    }
+}
+
+/// The temperature the annealing of [`Substitution::crack_with`] starts from, in log10
+/// of probability of the whole plaintext.
+#[cfg(feature = "analysis")]
+const INITIAL_TEMPERATURE: f64 = 20.0;
+
+/// Simple Substitution Cipher
+///
+/// Every letter of the alphabet is replaced by the letter at the same position in the
+/// cipher alphabet, a permutation of the alphabet.
+///
+/// The struct is generated through the new() function or the keyword() function.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    mapping: Vec<usize>,
+    inverse: Vec<usize>,
+    alphabet: Alphabet,
+}
+
+impl Substitution {
+    /// Initialize a substitution cipher with a cipher alphabet, a permutation of the 26
+    /// letters of the latin alphabet.
+    ///
+    /// # Examples:
+    /// - Initialization with a permutation:
+    /// ```
+    /// use cienli::ciphers::substitution::Substitution;
+    /// let substitution = Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXY");
+    ///
+    /// assert!(substitution.is_ok());
+    /// ```
+    ///
+    /// - Initialization with a repeated letter:
+    /// ```
+    /// use cienli::ciphers::substitution::Substitution;
+    /// let substitution = Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXZ");
+    ///
+    /// assert!(substitution.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher alphabet must have each latin letter exactly once.
+    pub fn new(cipher_alphabet: &str) -> Result<Substitution, Error> {
+        Substitution::with_alphabet(cipher_alphabet, Alphabet::latin())
+    }
+
+    /// Initialize a substitution cipher with a cipher alphabet over a custom alphabet.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::substitution::Substitution;
+    /// use cienli::common::Alphabet;
+    /// let substitution = Substitution::with_alphabet("ΩΨΧΦΥΤΣΡΠΟΞΝΜΛΚΙΘΗΖΕΔΓΒΑ", Alphabet::greek()).unwrap();
+    ///
+    /// assert_eq!("Χυπω", substitution.encipher("Γεια"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher alphabet must have each letter of the alphabet exactly once.
+    pub fn with_alphabet(cipher_alphabet: &str, alphabet: Alphabet) -> Result<Substitution, Error> {
+        let mapping = cipher_alphabet
+            .chars()
+            .map(|character| match alphabet.index_of(character) {
+                Some((index, _)) => Ok(index),
+                None => Err(Error::UnsupportedCharacter(character)),
+            })
+            .collect::<Result<Vec<usize>, Error>>()?;
+
+        Substitution::from_mapping(mapping, alphabet)
+    }
+
+    /// Initialize a substitution cipher with a keyword mixed alphabet, the repeated
+    /// letters of the keyword are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::substitution::{KeywordPlacement, Substitution};
+    /// let start = Substitution::keyword("Zebras", KeywordPlacement::Start).unwrap();
+    /// let last = Substitution::keyword("Zebras", KeywordPlacement::AfterLastLetter).unwrap();
+    ///
+    /// assert_eq!("ZEBRASCDFGHIJKLMNOPQTUVWXY", start.cipher_alphabet());
+    /// assert_eq!("ZEBRASTUVWXYCDFGHIJKLMNOPQ", last.cipher_alphabet());
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters and spaces.
+    pub fn keyword(keyword: &str, placement: KeywordPlacement) -> Result<Substitution, Error> {
+        let alphabet = Alphabet::latin();
+        let length = alphabet.len();
+
+        let mut keyword_letters = Vec::new();
+        for character in keyword.chars().filter(|character| *character != ' ') {
+            let (index, _) = alphabet
+                .index_of(character)
+                .ok_or(Error::UnsupportedCharacter(character))?;
+            if !keyword_letters.contains(&index) {
+                keyword_letters.push(index);
+            }
+        }
+
+        let start = match placement {
+            KeywordPlacement::AfterLastLetter => keyword_letters.last().map_or(0, |last| last + 1),
+            _ => 0,
+        };
+        let others = (0..length)
+            .map(|index| (start + index) % length)
+            .filter(|index| !keyword_letters.contains(index))
+            .collect::<Vec<usize>>();
+
+        let offset = match placement {
+            KeywordPlacement::End => length - keyword_letters.len(),
+            KeywordPlacement::Offset(offset) => offset % length,
+            _ => 0,
+        };
+        let mut mapping = keyword_letters;
+        mapping.extend(others);
+        mapping.rotate_right(offset);
+
+        Substitution::from_mapping(mapping, alphabet)
+    }
+
+    /// Initialize a substitution cipher with the position in the alphabet of the
+    /// substitute of each letter.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::substitution::Substitution;
+    /// use cienli::common::Alphabet;
+    /// let substitution = Substitution::from_mapping((0..26).rev().collect(), Alphabet::latin()).unwrap();
+    ///
+    /// assert_eq!("Svool :)", substitution.encipher("Hello :)"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The mapping must have each position of the alphabet exactly once.
+    pub fn from_mapping(mapping: Vec<usize>, alphabet: Alphabet) -> Result<Substitution, Error> {
+        if mapping.len() != alphabet.len() {
+            return Err(Error::InvalidKey(
+                "The cipher alphabet must have as many letters as the alphabet",
+            ));
+        }
+
+        let mut inverse = vec![alphabet.len(); alphabet.len()];
+        for (index, substitute) in mapping.iter().enumerate() {
+            match inverse.get_mut(*substitute) {
+                Some(letter) if *letter == alphabet.len() => *letter = index,
+                _ => {
+                    return Err(Error::InvalidKey(
+                        "The cipher alphabet must have each letter exactly once",
+                    ))
+                }
+            }
+        }
+
+        Ok(Substitution {
+            mapping,
+            inverse,
+            alphabet,
+        })
+    }
+
+    /// Returns the cipher alphabet in uppercase.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::substitution::{KeywordPlacement, Substitution};
+    /// let substitution = Substitution::keyword("Kryptos", KeywordPlacement::Offset(3)).unwrap();
+    ///
+    /// assert_eq!("WXZKRYPTOSABCDEFGHIJLMNQUV", substitution.cipher_alphabet());
+    /// ```
+    pub fn cipher_alphabet(&self) -> String {
+        self.mapping
+            .iter()
+            .map(|index| self.alphabet.letter(*index, Case::Upper))
+            .collect()
+    }
+
+    /// Enciphers a message with the substitution cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::substitution::Substitution;
+    /// let substitution = Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXY").unwrap();
+    ///
+    /// assert_eq!("Siaa zq rzvk!", substitution.encipher("Flee at dawn!"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        message
+            .chars()
+            .map(|character| self.substitute(character, &self.mapping))
+            .collect()
+    }
+
+    /// Deciphers a cipher with the substitution cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::substitution::Substitution;
+    /// let substitution = Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXY").unwrap();
+    ///
+    /// assert_eq!("Flee at dawn!", substitution.decipher("Siaa zq rzvk!"));
+    /// ```
+    pub fn decipher(&self, cipher: &str) -> String {
+        cipher
+            .chars()
+            .map(|character| self.substitute(character, &self.inverse))
+            .collect()
+    }
+
+    /// Recovers the key of an english ciphertext with the english quadgram model and the
+    /// default options. Returns None if the ciphertext has fewer letters than a quadgram.
+    ///
+    /// The letters which do not appear in the ciphertext are left in an arbitrary order
+    /// in the recovered key. The default options seed the search from the clock, the same
+    /// search is reproducible with [`Substitution::crack_with`] and a fixed seed.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    /// use cienli::ciphers::substitution::{CrackOptions, Substitution};
+    /// let substitution = Substitution::new("QWERTYUIOPASDFGHJKLZXCVBNM").unwrap();
+    /// let cipher = substitution.encipher(
+    ///     "The old lighthouse keeper climbed the narrow stairs every evening to light the great \
+    ///     lamp, and from the top he could see the fishing boats coming home across the grey water \
+    ///     while the gulls circled above the harbour.",
+    /// );
+    ///
+    /// let options = CrackOptions { seed: Some(42), ..CrackOptions::default() };
+    /// let candidate =
+    ///     Substitution::crack_with(&cipher, NgramModel::english_quadgrams(), options).unwrap();
+    /// assert!(candidate.plaintext.starts_with("The old lighthouse keeper"));
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack(cipher: &str) -> Option<Candidate<Substitution>> {
+        Substitution::crack_with(
+            cipher,
+            NgramModel::english_quadgrams(),
+            CrackOptions::default(),
+        )
+    }
+
+    /// Recovers the key of a ciphertext over the alphabet of a language model by simulated
+    /// annealing with random restarts, the key whose plaintext has the best score wins.
+    /// Returns None if the ciphertext has fewer letters than the n-grams of the model.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::analysis::NgramModel;
+    /// use cienli::ciphers::substitution::{CrackOptions, Substitution};
+    /// let substitution = Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXY").unwrap();
+    /// let cipher = substitution.encipher(
+    ///     "The committee met on a wet Thursday evening to decide what should be done with the old \
+    ///     mill by the river. Some members wanted to turn it into a museum, others preferred to \
+    ///     sell it to a company that would build flats, and a few believed that the town could \
+    ///     not afford either plan until the bridge had been repaired.",
+    /// );
+    ///
+    /// let options = CrackOptions { seed: Some(7), restarts: 3, iterations: 20000 };
+    /// let candidate = Substitution::crack_with(&cipher, NgramModel::english_quadgrams(), options).unwrap();
+    /// assert!(candidate.plaintext.starts_with("The committee met on a wet Thursday evening"));
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack_with(
+        cipher: &str,
+        model: &NgramModel,
+        options: CrackOptions,
+    ) -> Option<Candidate<Substitution>> {
+        let alphabet = model.alphabet();
+        let length = alphabet.len();
+        let letters = letters(cipher, alphabet).collect::<Vec<usize>>();
+
+        if letters.len() < model.n() || length < 2 {
+            return None;
+        }
+
+        let mut positions = vec![Vec::new(); length];
+        for (position, letter) in letters.iter().enumerate() {
+            positions[*letter].push(position);
+        }
+
+        let mut random = SplitMix::new(options.seed);
+        let mut best: Option<(Vec<usize>, f64)> = None;
+
+        for _ in 0..options.restarts.max(1) {
+            // The key gives the plaintext letter of every ciphertext letter.
+            let mut key = (0..length).collect::<Vec<usize>>();
+            for index in (1..length).rev() {
+                key.swap(index, random.below(index + 1));
+            }
+
+            let mut plaintext = letters
+                .iter()
+                .map(|letter| key[*letter])
+                .collect::<Vec<usize>>();
+            let mut score = model.score_letters(&plaintext);
+
+            let windows = (letters.len() + 1 - model.n()) as f64;
+            let (mut best_key, mut best_score) = (key.clone(), score);
+
+            for iteration in 0..options.iterations {
+                let temperature =
+                    INITIAL_TEMPERATURE * (1.0 - iteration as f64 / options.iterations as f64);
+                let first = random.below(length);
+                let second = (first + 1 + random.below(length - 1)) % length;
+
+                swap_letters(&mut key, &mut plaintext, &positions, first, second);
+
+                // The difference is scaled from the average to the total log-probability.
+                let candidate_score = model.score_letters(&plaintext);
+                let difference = (candidate_score - score) * windows;
+                if difference >= 0.0 || random.unit() < (difference / temperature).exp() {
+                    score = candidate_score;
+                    if score > best_score {
+                        best_key.clone_from(&key);
+                        best_score = score;
+                    }
+                } else {
+                    swap_letters(&mut key, &mut plaintext, &positions, first, second);
+                }
+            }
+
+            if best.as_ref().map_or(true, |(_, score)| best_score > *score) {
+                best = Some((best_key, best_score));
+            }
+        }
+
+        let (key, score) = best?;
+        let mut mapping = vec![0; length];
+        for (cipher_letter, plain_letter) in key.iter().enumerate() {
+            mapping[*plain_letter] = cipher_letter;
+        }
+
+        let substitution = Substitution::from_mapping(mapping, alphabet.clone()).ok()?;
+        Some(Candidate {
+            plaintext: substitution.decipher(cipher),
+            key: substitution,
+            score,
+        })
+    }
+
+    fn substitute(&self, character: char, mapping: &[usize]) -> char {
+        match self.alphabet.index_of(character) {
+            Some((index, case)) => self.alphabet.letter(mapping[index], case),
+            None => character,
+        }
+    }
+}
+
+/// Swaps two letters of a key and updates the plaintext letters enciphered with them.
+#[cfg(feature = "analysis")]
+fn swap_letters(
+    key: &mut [usize],
+    plaintext: &mut [usize],
+    positions: &[Vec<usize>],
+    first: usize,
+    second: usize,
+) {
+    key.swap(first, second);
+    for letter in [first, second] {
+        for position in positions[letter].iter() {
+            plaintext[*position] = key[letter];
+        }
+    }
+}
+
+/// A small seeded pseudorandom generator (SplitMix64) for the restarts of the cracker.
+#[cfg(feature = "analysis")]
+struct SplitMix(u64);
+
+#[cfg(feature = "analysis")]
+impl SplitMix {
+    fn new(seed: Option<u64>) -> SplitMix {
+        let seed = seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64)
+        });
+
+        SplitMix(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.0;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a number between 0.0 and 1.0.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number lower than the bound.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+impl Cipher for Substitution {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Substitution::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Substitution::decipher(self, cipher))
+    }
+}
+
+impl StreamCipher for Substitution {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        _position: &mut usize,
+        direction: Direction,
+    ) -> usize {
+        let mapping = match direction {
+            Direction::Encipher => &self.mapping,
+            Direction::Decipher => &self.inverse,
+        };
+
+        transform_chars(input, output, |character| {
+            self.substitute(character, mapping)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeywordPlacement, Substitution};
+    use crate::common::Alphabet;
+    use crate::Error;
+
+    #[test]
+    fn encipher_test() {
+        let substitution = Substitution::new("zebrascdfghijklmnopqtuvwxy").unwrap();
+
+        assert_eq!(
+            "SIAA ZQ LKBA! VA ZOA RFPBLUAOAR.",
+            substitution.encipher("FLEE AT ONCE! WE ARE DISCOVERED.")
+        );
+    }
+
+    #[test]
+    fn decipher_test() {
+        let substitution = Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWXY").unwrap();
+
+        assert_eq!(
+            "Flee at once! We are discovered.",
+            substitution.decipher("Siaa zq lkba! Va zoa rfpbluaoar.")
+        );
+    }
+
+    #[test]
+    fn keyword_placement_test() {
+        let cipher_alphabet = |placement| {
+            Substitution::keyword("KEYWORD", placement)
+                .unwrap()
+                .cipher_alphabet()
+        };
+
+        assert_eq!(
+            "KEYWORDABCFGHIJLMNPQSTUVXZ",
+            cipher_alphabet(KeywordPlacement::Start)
+        );
+        assert_eq!(
+            "ABCFGHIJLMNPQSTUVXZKEYWORD",
+            cipher_alphabet(KeywordPlacement::End)
+        );
+        assert_eq!(
+            "XZKEYWORDABCFGHIJLMNPQSTUV",
+            cipher_alphabet(KeywordPlacement::Offset(2))
+        );
+        assert_eq!(
+            "KEYWORDFGHIJLMNPQSTUVXZABC",
+            cipher_alphabet(KeywordPlacement::AfterLastLetter)
+        );
+    }
+
+    #[test]
+    fn custom_alphabet_test() {
+        let substitution =
+            Substitution::with_alphabet("ЯЮЭЬЫЪЩШЧЦХФУТСРПОНМЛКЙИЗЖЁЕДГВБА", Alphabet::cyrillic())
+                .unwrap();
+
+        assert_eq!(
+            "Привет",
+            substitution.decipher(&substitution.encipher("Привет"))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_test() {
+        use super::CrackOptions;
+        use crate::analysis::NgramModel;
+
+        let plaintext =
+            "My grandmother kept a small garden behind the bakery, where she grew beans, \
+            onions and a row of tall yellow flowers that the neighbours admired every summer. \
+            Each morning she would walk between the beds with a jug of water, pulling out weeds \
+            and telling the birds to leave her cherries alone, although they never listened.";
+        let substitution = Substitution::keyword("Orchard", KeywordPlacement::End).unwrap();
+        let cipher = substitution.encipher(plaintext);
+
+        let options = CrackOptions {
+            seed: Some(42),
+            restarts: 3,
+            iterations: 20000,
+        };
+        let model = NgramModel::english_quadgrams();
+        let candidate = Substitution::crack_with(&cipher, model, options).unwrap();
+
+        assert_eq!(plaintext, candidate.plaintext);
+        assert_eq!(
+            candidate,
+            Substitution::crack_with(&cipher, model, options).unwrap()
+        );
+        assert_eq!(None, Substitution::crack_with("Abc", model, options));
+    }
+
+    #[test]
+    fn invalid_test() {
+        assert_eq!(
+            Some(Error::UnsupportedCharacter('1')),
+            Substitution::new("ZEBRASCDFGHIJKLMNOPQTUVWX1").err()
+        );
+        assert!(Substitution::new("ZEBRAS").is_err());
+        assert!(
+            Substitution::from_mapping(vec![0, 1, 1], Alphabet::caseless("ABC").unwrap()).is_err()
+        );
+        assert!(
+            Substitution::from_mapping(vec![0, 1, 3], Alphabet::caseless("ABC").unwrap()).is_err()
+        );
+        assert!(Substitution::keyword("Key-word", KeywordPlacement::Start).is_err());
+    }
+}
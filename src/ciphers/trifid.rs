@@ -0,0 +1,244 @@
+use crate::ciphers::Cipher;
+use crate::Error;
+
+/// The 27th symbol of the cube, after the 26 latin letters.
+const EXTRA_SYMBOL: char = '+';
+
+/// Trifid Cipher
+///
+/// The layers, rows and columns of the symbols in a 3x3x3 cube are written in three lines,
+/// then read in triples to get the symbols of the cipher.
+///
+/// The struct is generated through the new() function.
+///
+pub struct Trifid {
+    cube: Vec<char>,
+    period: Option<usize>,
+}
+
+impl Trifid {
+    /// Initialize a trifid cipher with the keyword of its cube, the whole message is
+    /// fractionated at once.
+    ///
+    /// The cube holds the 26 latin letters and '+', the keyword is followed by the other symbols.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid keyword:
+    /// ```
+    /// use cienli::ciphers::trifid::Trifid;
+    /// let trifid = Trifid::new("Felix Marie Delastelle");
+    ///
+    /// assert!(trifid.is_ok());
+    /// ```
+    ///
+    /// - Initialization with an invalid keyword:
+    /// ```
+    /// use cienli::ciphers::trifid::Trifid;
+    /// let trifid = Trifid::new("Félix");
+    ///
+    /// assert!(trifid.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters, '+' and spaces.
+    pub fn new(keyword: &str) -> Result<Trifid, Error> {
+        Trifid::with_period(keyword, None)
+    }
+
+    /// Initialize a trifid cipher with the keyword of its cube and the period, the number
+    /// of symbols fractionated together. Without a period the whole message is fractionated
+    /// at once.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::trifid::Trifid;
+    /// let trifid = Trifid::with_period("Felix Marie Delastelle", Some(5)).unwrap();
+    ///
+    /// assert_eq!("FMJFVOISSUFTFPUFEQQC", trifid.encipher("Aide-toi, le ciel t'aidera"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters, '+' and spaces, and the period
+    /// must not be zero.
+    pub fn with_period(keyword: &str, period: Option<usize>) -> Result<Trifid, Error> {
+        if period == Some(0) {
+            return Err(Error::InvalidKey("Period cannot be zero"));
+        }
+
+        let mut cube = Vec::with_capacity(27);
+        for character in keyword.chars().filter(|character| *character != ' ') {
+            let symbol =
+                Trifid::normalize(character).ok_or(Error::UnsupportedCharacter(character))?;
+            if !cube.contains(&symbol) {
+                cube.push(symbol);
+            }
+        }
+
+        for symbol in ('A'..='Z').chain([EXTRA_SYMBOL]) {
+            if !cube.contains(&symbol) {
+                cube.push(symbol);
+            }
+        }
+
+        Ok(Trifid { cube, period })
+    }
+
+    /// Enciphers a message with the trifid cipher, the characters outside of the cube
+    /// are removed.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::trifid::Trifid;
+    /// let trifid = Trifid::new("Felix Marie Delastelle").unwrap();
+    ///
+    /// let cipher = trifid.encipher("Attack at dawn");
+    ///
+    /// assert_eq!(12, cipher.len());
+    /// assert_eq!("ATTACKATDAWN", trifid.decipher(&cipher).unwrap());
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        let positions = message
+            .chars()
+            .filter_map(|character| self.position(character))
+            .collect::<Vec<[usize; 3]>>();
+
+        self.blocks(&positions)
+            .flat_map(|block| {
+                let coordinates = (0..3)
+                    .flat_map(|axis| block.iter().map(move |position| position[axis]))
+                    .collect::<Vec<usize>>();
+
+                coordinates
+                    .chunks(3)
+                    .map(|triple| self.symbol([triple[0], triple[1], triple[2]]))
+                    .collect::<Vec<char>>()
+            })
+            .collect()
+    }
+
+    /// Deciphers a cipher with the trifid cipher, the whitespaces are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::trifid::Trifid;
+    /// let trifid = Trifid::with_period("Felix Marie Delastelle", Some(5)).unwrap();
+    ///
+    /// assert_eq!("AIDETOILECIELTAIDERA", trifid.decipher("FMJFV OISSU FTFPU FEQQC").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher must only contain symbols of the cube and whitespaces.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        let mut positions = Vec::new();
+        for character in cipher
+            .chars()
+            .filter(|character| !character.is_whitespace())
+        {
+            positions.push(
+                self.position(character)
+                    .ok_or(Error::UnsupportedCharacter(character))?,
+            );
+        }
+
+        Ok(self
+            .blocks(&positions)
+            .flat_map(|block| {
+                let coordinates = block.iter().flatten().copied().collect::<Vec<usize>>();
+                let length = block.len();
+
+                (0..length)
+                    .map(|index| {
+                        self.symbol([
+                            coordinates[index],
+                            coordinates[length + index],
+                            coordinates[2 * length + index],
+                        ])
+                    })
+                    .collect::<Vec<char>>()
+            })
+            .collect())
+    }
+
+    fn normalize(character: char) -> Option<char> {
+        match character.to_ascii_uppercase() {
+            symbol @ 'A'..='Z' => Some(symbol),
+            EXTRA_SYMBOL => Some(EXTRA_SYMBOL),
+            _ => None,
+        }
+    }
+
+    /// Returns the layer, the row and the column of a character in the cube.
+    fn position(&self, character: char) -> Option<[usize; 3]> {
+        let symbol = Trifid::normalize(character)?;
+
+        self.cube
+            .iter()
+            .position(|cell| *cell == symbol)
+            .map(|index| [index / 9, index / 3 % 3, index % 3])
+    }
+
+    fn symbol(&self, position: [usize; 3]) -> char {
+        self.cube[position[0] * 9 + position[1] * 3 + position[2]]
+    }
+
+    fn blocks<'a>(&self, positions: &'a [[usize; 3]]) -> impl Iterator<Item = &'a [[usize; 3]]> {
+        positions.chunks(self.period.unwrap_or(positions.len()).max(1))
+    }
+}
+
+impl Cipher for Trifid {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Trifid::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Trifid::decipher(self, cipher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Trifid;
+    use crate::Error;
+
+    const KEYWORD: &str = "FELIX MARIE DELASTELLE";
+
+    #[test]
+    fn encipher_test() {
+        let trifid = Trifid::with_period(KEYWORD, Some(5)).unwrap();
+
+        assert_eq!(
+            "FMJFVOISSUFTFPUFEQQC",
+            trifid.encipher("Aide-toi, le ciel t'aidera")
+        );
+    }
+
+    #[test]
+    fn decipher_test() {
+        let trifid = Trifid::with_period(KEYWORD, Some(5)).unwrap();
+
+        assert_eq!(
+            "AIDETOILECIELTAIDERA",
+            trifid.decipher("FMJFVOISSUFTFPUFEQQC").unwrap()
+        );
+    }
+
+    #[test]
+    fn whole_message_test() {
+        let trifid = Trifid::new(KEYWORD).unwrap();
+        let cipher = trifid.encipher("Meet me at the bridge+");
+
+        assert_eq!("MEETMEATTHEBRIDGE+", trifid.decipher(&cipher).unwrap());
+    }
+
+    #[test]
+    fn invalid_test() {
+        let trifid = Trifid::new("").unwrap();
+
+        assert!(Trifid::with_period("", Some(0)).is_err());
+        assert_eq!(
+            Err(Error::UnsupportedCharacter('.')),
+            trifid.decipher("AB.")
+        );
+    }
+}
@@ -1,52 +1,228 @@
-use crate::common::key_gen;
+#[cfg(feature = "analysis")]
+use crate::analysis::{chi_squared_counts, index_of_coincidence, letters, ENGLISH_FREQUENCIES};
+use crate::ciphers::Cipher;
+use crate::common::Alphabet;
+use crate::stream::{transform_chars, Direction, StreamCipher};
+use crate::Error;
+#[cfg(feature = "analysis")]
+use std::collections::HashMap;
+
+/// The longest key tried by [`Vigenere::crack`].
+#[cfg(feature = "analysis")]
+const MAX_KEY_LENGTH: usize = 20;
+
+/// Index of coincidence of english texts and of uniformly random letters.
+#[cfg(feature = "analysis")]
+const ENGLISH_IOC: f64 = 0.0667;
+#[cfg(feature = "analysis")]
+const RANDOM_IOC: f64 = 1.0 / 26.0;
+
+/// The key and plaintext recovered by [`Vigenere::crack`]
+///
+/// The confidence is between 0.0 and 1.0, it measures how close the letters enciphered
+/// with the same key letter are to english (1.0) rather than to random letters (0.0).
+#[cfg(feature = "analysis")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VigenereSolution {
+    pub key: String,
+    pub plaintext: String,
+    pub confidence: f64,
+}
+
+/// How a letter of the message is combined with a letter of the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tableau {
+    /// The key is added to the message (tabula recta).
+    Vigenere,
+    /// The message is subtracted from the key, enciphering and deciphering are the same operation.
+    Beaufort,
+    /// The key is subtracted from the message.
+    VariantBeaufort,
+}
+
+impl Tableau {
+    /// Returns the index of the letter combining a letter of the text with a letter of the key.
+    fn apply(self, index: usize, shift: usize, length: usize, direction: Direction) -> usize {
+        let output = match (self, direction) {
+            (Tableau::Beaufort, _) => length + shift - index,
+            (Tableau::Vigenere, Direction::Encipher)
+            | (Tableau::VariantBeaufort, Direction::Decipher) => index + shift,
+            _ => length + index - shift,
+        };
+
+        output % length
+    }
+}
 
 /// Vigenere Cipher
 ///
-/// The struct is generated through the new() function.
+/// The struct is generated through the new() function, the beaufort(), variant_beaufort()
+/// and gronsfeld() functions generate its periodic siblings.
 ///
-pub struct Vigenere<'a> {
-    key: &'a str,
+/// The key only moves forward on the letters of the alphabet, the other characters are kept
+/// without using a letter of the key.
+///
+pub struct Vigenere {
+    shifts: Vec<usize>,
+    alphabet: Alphabet,
+    tableau: Tableau,
 }
 
-impl Vigenere<'_> {
+impl Vigenere {
     /// Initialize a vignere cipher with a key.
     ///
     /// # Examples:
     /// ```
     /// use cienli::ciphers::vigenere::Vigenere;
-    /// let vigenere = Vigenere::new("ABCDE");
+    /// let vigenere = Vigenere::new("ABCDE").unwrap();
     /// ```
-    pub fn new(key: &str) -> Vigenere {
-        Vigenere { key }
+    ///
+    /// # Errors:
+    /// The key must not be empty and must only contain letters of the latin alphabet.
+    pub fn new(key: &str) -> Result<Vigenere, Error> {
+        Vigenere::with_alphabet(key, Alphabet::latin())
     }
 
-    /// Enciphers a message with the vigenere cipher.
+    /// Initialize a vignere cipher with a key over a custom alphabet.
     ///
     /// # Examples:
     /// ```
     /// use cienli::ciphers::vigenere::Vigenere;
-    /// let vigenere = Vigenere::new("ABcdE");
+    /// use cienli::common::Alphabet;
+    /// let vigenere = Vigenere::with_alphabet("ÄB", Alphabet::german()).unwrap();
     ///
-    /// assert_eq!("Qxgux :)", vigenere.encipher("Qwert :)"));
+    /// assert_eq!("Ävzfj", vigenere.encipher("Außen"));
     /// ```
-    pub fn encipher(&self, message: &str) -> String {
-        let key = key_gen(&self.key.to_uppercase(), message.len())
-            .unwrap()
-            .as_bytes()
-            .to_owned();
+    ///
+    /// # Errors:
+    /// The key must not be empty and must only contain letters of the alphabet.
+    pub fn with_alphabet(key: &str, alphabet: Alphabet) -> Result<Vigenere, Error> {
+        Vigenere::with_tableau(key, alphabet, Tableau::Vigenere)
+    }
+
+    /// Initialize a beaufort cipher with a key, the message is subtracted from the key.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// let beaufort = Vigenere::beaufort("FORTIFICATION").unwrap();
+    ///
+    /// assert_eq!("CKMPVCPVWPIWUJOGIUAPVWRIWUUK", beaufort.encipher("DEFENDTHEEASTWALLOFTHECASTLE"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty and must only contain letters of the latin alphabet.
+    pub fn beaufort(key: &str) -> Result<Vigenere, Error> {
+        Vigenere::with_tableau(key, Alphabet::latin(), Tableau::Beaufort)
+    }
+
+    /// Initialize a variant beaufort cipher with a key, the key is subtracted from the message.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// let variant = Vigenere::variant_beaufort("LEMON").unwrap();
+    ///
+    /// assert_eq!("PPHMPZWHPNLJ", variant.encipher("ATTACKATDAWN"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty and must only contain letters of the latin alphabet.
+    pub fn variant_beaufort(key: &str) -> Result<Vigenere, Error> {
+        Vigenere::with_tableau(key, Alphabet::latin(), Tableau::VariantBeaufort)
+    }
 
-        let message = message.as_bytes();
+    /// Initialize a gronsfeld cipher with a numeric key, each digit is the shift
+    /// of a letter of the message.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid key:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// let gronsfeld = Vigenere::gronsfeld("31415").unwrap();
+    ///
+    /// assert_eq!("Duxbhn bx efzo", gronsfeld.encipher("Attack at dawn"));
+    /// ```
+    ///
+    /// - Initialization with an invalid key:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// let gronsfeld = Vigenere::gronsfeld("LEMON");
+    ///
+    /// assert!(gronsfeld.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty and must only contain digits.
+    pub fn gronsfeld(key: &str) -> Result<Vigenere, Error> {
+        if key.is_empty() {
+            return Err(Error::EmptyKey);
+        }
+        let shifts = key
+            .chars()
+            .map(|character| match character.to_digit(10) {
+                Some(digit) => Ok(digit as usize),
+                None => Err(Error::UnsupportedCharacter(character)),
+            })
+            .collect::<Result<Vec<usize>, Error>>()?;
 
-        let mut result: String = String::new();
+        Ok(Vigenere {
+            shifts,
+            alphabet: Alphabet::latin(),
+            tableau: Tableau::Vigenere,
+        })
+    }
 
-        for indx in 0..message.len() {
-            result.push(match message[indx] as char {
-                'A'..='Z' => (((key[indx] + message[indx]) % 26) + 65) as char,
-                'a'..='z' => ((((key[indx] - 32) + message[indx]) % 26) + 97) as char,
-                _ => message[indx] as char,
-            });
+    /// Initialize a periodic cipher with a key over a custom alphabet and the tableau
+    /// combining the letters of the message with the letters of the key.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::{Tableau, Vigenere};
+    /// use cienli::common::Alphabet;
+    /// let beaufort = Vigenere::with_tableau("ключ", Alphabet::cyrillic(), Tableau::Beaufort).unwrap();
+    ///
+    /// assert_eq!("Привет", beaufort.encipher(&beaufort.encipher("Привет")));
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty and must only contain letters of the alphabet.
+    pub fn with_tableau(
+        key: &str,
+        alphabet: Alphabet,
+        tableau: Tableau,
+    ) -> Result<Vigenere, Error> {
+        if key.is_empty() {
+            return Err(Error::EmptyKey);
         }
-        result
+        let shifts = key
+            .chars()
+            .map(|character| match alphabet.index_of(character) {
+                Some((index, _)) => Ok(index),
+                None => Err(Error::UnsupportedCharacter(character)),
+            })
+            .collect::<Result<Vec<usize>, Error>>()?;
+
+        Ok(Vigenere {
+            shifts,
+            alphabet,
+            tableau,
+        })
+    }
+
+    /// Enciphers a message with the vigenere cipher.
+    ///
+    /// The key only moves forward on the letters of the alphabet.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// let vigenere = Vigenere::new("ABcdE").unwrap();
+    ///
+    /// assert_eq!("Qxgux :)", vigenere.encipher("Qwert :)"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        self.shift(message, Direction::Encipher)
     }
 
     /// Deciphers a cipher with the vigenere cipher.
@@ -54,45 +230,683 @@ impl Vigenere<'_> {
     /// # Examples:
     /// ```
     /// use cienli::ciphers::vigenere::Vigenere;
-    /// let vigenere = Vigenere::new("ABcdE");
+    /// let vigenere = Vigenere::new("ABcdE").unwrap();
     ///
     /// assert_eq!("Qwert :)", vigenere.decipher("Qxgux :)"));
+    /// ```
     pub fn decipher(&self, message: &str) -> String {
-        let key = key_gen(&self.key.to_uppercase(), message.len())
-            .unwrap()
-            .as_bytes()
-            .to_owned();
+        self.shift(message, Direction::Decipher)
+    }
+
+    /// Recovers the key of an english ciphertext enciphered with the latin alphabet.
+    ///
+    /// The key length is estimated with the Kasiski examination and the index of coincidence,
+    /// then every letter of the key is found by the frequency analysis of its column.
+    /// Returns None if the ciphertext has no letters.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// let vigenere = Vigenere::new("LEMON").unwrap();
+    /// let cipher = vigenere.encipher(
+    ///     "The harbour master kept a ledger of every boat that left before dawn, and in the \
+    ///     winter he wrote down the weather beside each name, so that anyone who read the pages \
+    ///     later could tell which crews had gone out in fog and which had waited for the wind to \
+    ///     turn. His daughter copied the ledger each spring into a clean book for the town hall.",
+    /// );
+    ///
+    /// let solution = Vigenere::crack(&cipher).unwrap();
+    /// assert_eq!("LEMON", solution.key);
+    /// assert!(solution.plaintext.starts_with("The harbour master kept a ledger"));
+    /// assert!(solution.confidence > 0.8);
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack(cipher: &str) -> Option<VigenereSolution> {
+        let alphabet = Alphabet::latin();
+        let letters = letters(cipher, &alphabet).collect::<Vec<usize>>();
+
+        if letters.is_empty() {
+            return None;
+        }
+
+        let key_length = Vigenere::estimate_key_length(&letters);
 
-        let message = message.as_bytes();
+        let key = (0..key_length)
+            .map(|column| {
+                let mut counts = vec![vec![0; 26]; 26];
+                for letter in letters.iter().skip(column).step_by(key_length) {
+                    for (shift, shifted_counts) in counts.iter_mut().enumerate() {
+                        shifted_counts[(letter + 26 - shift) % 26] += 1;
+                    }
+                }
 
-        let mut result: String = String::new();
+                (0..26)
+                    .min_by(|a, b| {
+                        chi_squared_counts(&counts[*a], &ENGLISH_FREQUENCIES)
+                            .total_cmp(&chi_squared_counts(&counts[*b], &ENGLISH_FREQUENCIES))
+                    })
+                    .map(|shift| (shift as u8 + 65) as char)
+                    .unwrap_or('A')
+            })
+            .collect::<String>();
+
+        let vigenere = Vigenere::new(&key).ok()?;
+        let confidence = ((average_ioc(&letters, key_length) - RANDOM_IOC)
+            / (ENGLISH_IOC - RANDOM_IOC))
+            .clamp(0.0, 1.0);
+
+        Some(VigenereSolution {
+            plaintext: vigenere.decipher(cipher),
+            key,
+            confidence,
+        })
+    }
+
+    /// Estimates the key length of a ciphertext from the positions of its letters in the alphabet.
+    ///
+    /// The lengths whose columns have an index of coincidence close to the best one are kept,
+    /// then the one that divides the most distances between repeated trigrams wins, the shortest
+    /// one on a tie as the multiples of the key length have the same index of coincidence.
+    #[cfg(feature = "analysis")]
+    fn estimate_key_length(letters: &[usize]) -> usize {
+        let max_length = (letters.len() / 2).clamp(1, MAX_KEY_LENGTH);
+
+        let iocs = (1..=max_length)
+            .map(|length| (length, average_ioc(letters, length)))
+            .collect::<Vec<(usize, f64)>>();
+        let best_ioc = iocs.iter().map(|(_, ioc)| *ioc).fold(0.0, f64::max);
+
+        let distances = repeated_distances(letters, 3);
+        let support = |length: usize| {
+            distances
+                .iter()
+                .filter(|distance| *distance % length == 0)
+                .count()
+        };
+
+        iocs.iter()
+            .filter(|(_, ioc)| *ioc >= 0.85 * best_ioc)
+            .map(|(length, _)| *length)
+            .fold(None, |best: Option<usize>, length| match best {
+                Some(best) if support(best) >= support(length) => Some(best),
+                _ => Some(length),
+            })
+            .unwrap_or(1)
+    }
 
-        for indx in 0..message.len() {
-            result.push(match message[indx] as char {
-                'A'..='Z' => (((26 + message[indx] - key[indx]) % 26) + 65) as char,
-                'a'..='z' => (((26 + message[indx] - (key[indx] + 32)) % 26) + 97) as char,
-                _ => message[indx] as char,
-            });
+    fn shift(&self, message: &str, direction: Direction) -> String {
+        let mut position = 0;
+
+        message
+            .chars()
+            .map(|character| self.shift_letter(character, &mut position, direction))
+            .collect()
+    }
+
+    fn shift_letter(&self, character: char, position: &mut usize, direction: Direction) -> char {
+        match self.alphabet.index_of(character) {
+            Some((index, case)) => {
+                let shift = self.shifts[*position % self.shifts.len()];
+                *position += 1;
+
+                let output = self
+                    .tableau
+                    .apply(index, shift, self.alphabet.len(), direction);
+                self.alphabet.letter(output, case)
+            }
+            None => character,
         }
-        result
+    }
+}
+
+/// Returns the average index of coincidence of the columns of letters enciphered with the same key letter.
+#[cfg(feature = "analysis")]
+fn average_ioc(letters: &[usize], key_length: usize) -> f64 {
+    let alphabet = Alphabet::latin();
+
+    let total = (0..key_length)
+        .map(|column| {
+            let column = letters
+                .iter()
+                .skip(column)
+                .step_by(key_length)
+                .map(|index| alphabet.letter(*index, crate::common::Case::Upper))
+                .collect::<String>();
+            index_of_coincidence(&column, &alphabet)
+        })
+        .sum::<f64>();
+
+    total / key_length as f64
+}
+
+/// Returns the distances between the consecutive occurrences of the repeated n-grams (Kasiski examination).
+#[cfg(feature = "analysis")]
+fn repeated_distances(letters: &[usize], n: usize) -> Vec<usize> {
+    let mut positions: HashMap<&[usize], Vec<usize>> = HashMap::new();
+    for (position, ngram) in letters.windows(n).enumerate() {
+        positions.entry(ngram).or_default().push(position);
+    }
+
+    positions
+        .values()
+        .flat_map(|positions| positions.windows(2).map(|pair| pair[1] - pair[0]))
+        .collect()
+}
+
+/// The text extending the primer of an [`Autokey`] cipher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutokeySource {
+    /// The key is the primer followed by the message.
+    Plaintext,
+    /// The key is the primer followed by the cipher.
+    Ciphertext,
+}
+
+/// Autokey Cipher
+///
+/// The key starts with the primer and is extended with the letters of the message
+/// (or of the cipher), so it never repeats.
+///
+/// The struct is generated through the new() function.
+///
+pub struct Autokey {
+    primer: Vec<usize>,
+    alphabet: Alphabet,
+    tableau: Tableau,
+    source: AutokeySource,
+}
+
+impl Autokey {
+    /// Initialize an autokey cipher with a primer, the key is extended with the message.
+    ///
+    /// # Examples:
+    /// - Initialization with a valid primer:
+    /// ```
+    /// use cienli::ciphers::vigenere::Autokey;
+    /// let autokey = Autokey::new("QUEENLY");
+    ///
+    /// assert!(autokey.is_ok());
+    /// ```
+    ///
+    /// - Initialization with an invalid primer:
+    /// ```
+    /// use cienli::ciphers::vigenere::Autokey;
+    /// let autokey = Autokey::new("QUEEN 1");
+    ///
+    /// assert!(autokey.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The primer must not be empty and must only contain letters of the latin alphabet.
+    pub fn new(primer: &str) -> Result<Autokey, Error> {
+        Autokey::with_options(
+            primer,
+            Alphabet::latin(),
+            Tableau::Vigenere,
+            AutokeySource::Plaintext,
+        )
+    }
+
+    /// Initialize an autokey cipher with a primer over a custom alphabet, the tableau
+    /// and the text extending the primer.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::{Autokey, AutokeySource, Tableau};
+    /// use cienli::common::Alphabet;
+    /// let autokey = Autokey::with_options("QUEENLY", Alphabet::latin(), Tableau::Vigenere, AutokeySource::Ciphertext).unwrap();
+    ///
+    /// assert_eq!("QNXEPVYJQXAC", autokey.encipher("ATTACKATDAWN"));
+    /// ```
+    ///
+    /// # Errors:
+    /// The primer must not be empty and must only contain letters of the alphabet.
+    pub fn with_options(
+        primer: &str,
+        alphabet: Alphabet,
+        tableau: Tableau,
+        source: AutokeySource,
+    ) -> Result<Autokey, Error> {
+        let vigenere = Vigenere::with_tableau(primer, alphabet, tableau)?;
+
+        Ok(Autokey {
+            primer: vigenere.shifts,
+            alphabet: vigenere.alphabet,
+            tableau,
+            source,
+        })
+    }
+
+    /// Enciphers a message with the autokey cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::Autokey;
+    /// let autokey = Autokey::new("QUEENLY").unwrap();
+    ///
+    /// assert_eq!("QNXEPV YT WTWP", autokey.encipher("ATTACK AT DAWN"));
+    /// ```
+    pub fn encipher(&self, message: &str) -> String {
+        self.shift(message, Direction::Encipher)
+    }
+
+    /// Deciphers a cipher with the autokey cipher, the key is rebuilt with the
+    /// deciphered letters.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::Autokey;
+    /// let autokey = Autokey::new("QUEENLY").unwrap();
+    ///
+    /// assert_eq!("ATTACK AT DAWN", autokey.decipher("QNXEPV YT WTWP"));
+    /// ```
+    pub fn decipher(&self, cipher: &str) -> String {
+        self.shift(cipher, Direction::Decipher)
+    }
+
+    fn shift(&self, text: &str, direction: Direction) -> String {
+        let mut key = self.primer.clone();
+        let mut position = 0;
+
+        text.chars()
+            .map(|character| match self.alphabet.index_of(character) {
+                Some((index, case)) => {
+                    let output =
+                        self.tableau
+                            .apply(index, key[position], self.alphabet.len(), direction);
+                    position += 1;
+
+                    let (plain, cipher) = match direction {
+                        Direction::Encipher => (index, output),
+                        Direction::Decipher => (output, index),
+                    };
+                    key.push(match self.source {
+                        AutokeySource::Plaintext => plain,
+                        AutokeySource::Ciphertext => cipher,
+                    });
+
+                    self.alphabet.letter(output, case)
+                }
+                None => character,
+            })
+            .collect()
+    }
+}
+
+impl Cipher for Autokey {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Autokey::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Autokey::decipher(self, cipher))
+    }
+}
+
+/// Running Key Cipher
+///
+/// The key is a long text such as a book passage, its letters are used once each so the
+/// key never repeats.
+///
+/// The struct is generated through the new() function.
+///
+pub struct RunningKey {
+    vigenere: Vigenere,
+}
+
+impl RunningKey {
+    /// Initialize a running key cipher with a text, the letters of the text are the key
+    /// and the other characters are skipped.
+    ///
+    /// # Examples:
+    /// - Initialization with a passage:
+    /// ```
+    /// use cienli::ciphers::vigenere::RunningKey;
+    /// let running_key = RunningKey::new("Call me Ishmael. Some years ago...");
+    ///
+    /// assert!(running_key.is_ok());
+    /// ```
+    ///
+    /// - Initialization with a text without letters:
+    /// ```
+    /// use cienli::ciphers::vigenere::RunningKey;
+    /// let running_key = RunningKey::new("1851");
+    ///
+    /// assert!(running_key.is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The text must contain letters of the latin alphabet.
+    pub fn new(text: &str) -> Result<RunningKey, Error> {
+        RunningKey::with_options(text, Alphabet::latin(), Tableau::Vigenere)
+    }
+
+    /// Initialize a running key cipher with a text over a custom alphabet and the tableau
+    /// combining the letters of the message with the letters of the text.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::{RunningKey, Tableau};
+    /// use cienli::common::Alphabet;
+    /// let running_key = RunningKey::with_options("Война и мир", Alphabet::cyrillic(), Tableau::Beaufort).unwrap();
+    ///
+    /// assert_eq!("Привет", running_key.decipher(&running_key.encipher("Привет").unwrap()).unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The text must contain letters of the alphabet.
+    pub fn with_options(
+        text: &str,
+        alphabet: Alphabet,
+        tableau: Tableau,
+    ) -> Result<RunningKey, Error> {
+        let shifts = text
+            .chars()
+            .filter_map(|character| alphabet.index_of(character))
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>();
+
+        if shifts.is_empty() {
+            return Err(Error::EmptyKey);
+        }
+
+        Ok(RunningKey {
+            vigenere: Vigenere {
+                shifts,
+                alphabet,
+                tableau,
+            },
+        })
+    }
+
+    /// Enciphers a message with the running key cipher.
+    ///
+    /// # Examples:
+    /// - Enciphering a message shorter than the text:
+    /// ```
+    /// use cienli::ciphers::vigenere::RunningKey;
+    /// let running_key = RunningKey::new("Call me Ishmael. Some years ago...").unwrap();
+    ///
+    /// assert_eq!("Cteloo il kmwr!", running_key.encipher("Attack at dawn!").unwrap());
+    /// ```
+    ///
+    /// - Enciphering a message longer than the text:
+    /// ```
+    /// use cienli::ciphers::vigenere::RunningKey;
+    /// let running_key = RunningKey::new("Call me").unwrap();
+    ///
+    /// assert!(running_key.encipher("Attack at dawn!").is_err());
+    /// ```
+    ///
+    /// # Errors:
+    /// The message must not have more letters than the text, the text is never repeated.
+    pub fn encipher(&self, message: &str) -> Result<String, Error> {
+        self.shift(message, Direction::Encipher)
+    }
+
+    /// Deciphers a cipher with the running key cipher.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::RunningKey;
+    /// let running_key = RunningKey::new("Call me Ishmael. Some years ago...").unwrap();
+    ///
+    /// assert_eq!("Attack at dawn!", running_key.decipher("Cteloo il kmwr!").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher must not have more letters than the text, the text is never repeated.
+    pub fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        self.shift(cipher, Direction::Decipher)
+    }
+
+    fn shift(&self, text: &str, direction: Direction) -> Result<String, Error> {
+        let alphabet = &self.vigenere.alphabet;
+        let letters = text
+            .chars()
+            .filter(|character| alphabet.index_of(*character).is_some())
+            .count();
+
+        if letters > self.vigenere.shifts.len() {
+            return Err(Error::InvalidKey(
+                "The key text has fewer letters than the message",
+            ));
+        }
+
+        Ok(self.vigenere.shift(text, direction))
+    }
+}
+
+impl Cipher for RunningKey {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        RunningKey::encipher(self, message)
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        RunningKey::decipher(self, cipher)
+    }
+}
+
+impl Cipher for Vigenere {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(Vigenere::encipher(self, message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        Ok(Vigenere::decipher(self, cipher))
+    }
+}
+
+impl StreamCipher for Vigenere {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        position: &mut usize,
+        direction: Direction,
+    ) -> usize {
+        transform_chars(input, output, |character| {
+            self.shift_letter(character, position, direction)
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Vigenere;
+    use super::{Autokey, AutokeySource, RunningKey, Tableau, Vigenere};
+    use crate::common::Alphabet;
+    use crate::stream::{CipherWriter, Direction};
+    use crate::Error;
+    use std::io::Write;
 
     #[test]
     fn encipher_test() {
-        let v = Vigenere::new("ABCDE");
+        let v = Vigenere::new("ABCDE").unwrap();
 
         assert_eq!("QXGUX :)", v.encipher("QWERT :)"));
     }
 
     #[test]
     fn decipher_test() {
-        let v = Vigenere::new("ABCDE");
+        let v = Vigenere::new("ABCDE").unwrap();
 
         assert_eq!("Qwert :)", v.decipher("Qxgux :)"));
     }
+
+    #[test]
+    fn invalid_key_test() {
+        assert_eq!(Some(Error::EmptyKey), Vigenere::new("").err());
+        assert_eq!(
+            Some(Error::UnsupportedCharacter('1')),
+            Vigenere::new("AB1").err()
+        );
+    }
+
+    #[test]
+    fn custom_alphabet_test() {
+        let v = Vigenere::with_alphabet("ключ", Alphabet::cyrillic()).unwrap();
+
+        assert_eq!("Привет, мир!", v.decipher(&v.encipher("Привет, мир!")));
+        assert!(Vigenere::with_alphabet("key", Alphabet::cyrillic()).is_err());
+    }
+
+    #[test]
+    fn key_skips_non_letters_test() {
+        let v = Vigenere::new("B").unwrap();
+
+        assert_eq!("Ifmmp, Xpsme", v.encipher("Hello, World"));
+        assert_eq!("b c", Vigenere::new("AB").unwrap().encipher("b b"));
+    }
+
+    #[test]
+    fn key_ignores_spaces_test() {
+        let v = Vigenere::new("LEMON").unwrap();
+
+        assert_eq!("Lxfopv ef rnhr", v.encipher("Attack at dawn"));
+        assert_eq!(
+            v.encipher("Attackatdawn"),
+            v.encipher("Attack at dawn").replace(' ', "")
+        );
+    }
+
+    #[test]
+    fn stream_keeps_key_position_test() {
+        let v = Vigenere::new("KEY").unwrap();
+        let mut writer = CipherWriter::new(Vec::new(), &v, Direction::Encipher);
+
+        for chunk in ["Attack", " at ", "dawn!"] {
+            writer.write_all(chunk.as_bytes()).unwrap();
+        }
+        let cipher = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        assert_eq!(v.encipher("Attack at dawn!"), cipher);
+        assert_eq!("Kxrkgi kx bkal!", cipher);
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_test() {
+        let plaintext = "The Kasiski examination takes advantage of the fact that repeated words \
+            are, by chance, sometimes encrypted using the same key letters, leading to repeated \
+            groups in the ciphertext. The distance between the repeated groups is a multiple of \
+            the length of the keyword, which gives the attacker a short list of likely lengths.";
+        let cipher = Vigenere::new("CRYPTO").unwrap().encipher(plaintext);
+
+        let solution = Vigenere::crack(&cipher).unwrap();
+        assert_eq!("CRYPTO", solution.key);
+        assert_eq!(plaintext, solution.plaintext);
+        assert!(solution.confidence > 0.5);
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack_without_letters_test() {
+        assert_eq!(None, Vigenere::crack("1234 :)"));
+    }
+
+    #[test]
+    fn beaufort_test() {
+        let beaufort = Vigenere::beaufort("FORTIFICATION").unwrap();
+
+        assert_eq!(
+            "CKMPVCPVWPIWUJOGIUAPVWRIWUUK",
+            beaufort.encipher("DEFENDTHEEASTWALLOFTHECASTLE")
+        );
+        assert_eq!(
+            "DEFENDTHEEASTWALLOFTHECASTLE",
+            beaufort.decipher("CKMPVCPVWPIWUJOGIUAPVWRIWUUK")
+        );
+    }
+
+    #[test]
+    fn variant_beaufort_test() {
+        let variant = Vigenere::variant_beaufort("LEMON").unwrap();
+        let vigenere = Vigenere::new("LEMON").unwrap();
+
+        assert_eq!(
+            vigenere.decipher("Attack at dawn"),
+            variant.encipher("Attack at dawn")
+        );
+        assert_eq!(
+            "Attack at dawn",
+            variant.decipher(&variant.encipher("Attack at dawn"))
+        );
+    }
+
+    #[test]
+    fn gronsfeld_test() {
+        let gronsfeld = Vigenere::gronsfeld("31415").unwrap();
+        let vigenere = Vigenere::new("DBEBF").unwrap();
+
+        assert_eq!(
+            vigenere.encipher("Attack at dawn"),
+            gronsfeld.encipher("Attack at dawn")
+        );
+        assert_eq!("Attack at dawn", gronsfeld.decipher("Duxbhn bx efzo"));
+        assert_eq!(Some(Error::EmptyKey), Vigenere::gronsfeld("").err());
+        assert_eq!(
+            Some(Error::UnsupportedCharacter('x')),
+            Vigenere::gronsfeld("3x").err()
+        );
+    }
+
+    #[test]
+    fn running_key_test() {
+        let passage =
+            "It was the best of times, it was the worst of times, it was the age of wisdom";
+        let running_key = RunningKey::new(passage).unwrap();
+        let message = "Meet me at the old bridge at midnight";
+
+        assert_eq!(
+            message,
+            running_key
+                .decipher(&running_key.encipher(message).unwrap())
+                .unwrap()
+        );
+        assert_eq!(Some(Error::EmptyKey), RunningKey::new("...").err());
+    }
+
+    #[test]
+    fn running_key_never_repeats_test() {
+        let running_key = RunningKey::new("Short").unwrap();
+
+        assert_eq!("Siort", running_key.encipher("Abaaa").unwrap());
+        assert!(running_key.encipher("Abaaaa").is_err());
+        assert!(running_key
+            .encipher("Forty letters of message text are far too many")
+            .is_err());
+        assert!(running_key.decipher("Siorts").is_err());
+    }
+
+    #[test]
+    fn autokey_test() {
+        let autokey = Autokey::new("QUEENLY").unwrap();
+
+        assert_eq!("QNXEPVYTWTWP", autokey.encipher("ATTACKATDAWN"));
+        assert_eq!("ATTACKATDAWN", autokey.decipher("QNXEPVYTWTWP"));
+    }
+
+    #[test]
+    fn autokey_options_test() {
+        let message = "Привет, мир! Как дела?";
+
+        for tableau in [
+            Tableau::Vigenere,
+            Tableau::Beaufort,
+            Tableau::VariantBeaufort,
+        ] {
+            for source in [AutokeySource::Plaintext, AutokeySource::Ciphertext] {
+                let autokey =
+                    Autokey::with_options("ключ", Alphabet::cyrillic(), tableau, source).unwrap();
+                assert_eq!(message, autokey.decipher(&autokey.encipher(message)));
+            }
+        }
+    }
+
+    #[test]
+    fn empty_message_test() {
+        let v = Vigenere::new("ABCDE").unwrap();
+
+        assert_eq!("", v.encipher(""));
+    }
 }
@@ -1,23 +1,72 @@
-use crate::common::key_gen;
+#[cfg(feature = "analysis")]
+use crate::analysis::english_score;
+use crate::ciphers::Cipher;
+use crate::common::{base64_decode, base64_encode, hex_decode, hex_encode};
+use crate::stream::{Direction, StreamCipher};
+use crate::Error;
+
+/// The longest key tried by [`Xor::crack`].
+#[cfg(feature = "analysis")]
+const MAX_KEY_SIZE: usize = 40;
+
+/// The fewest bytes enciphered with the same key byte for a key size to be tried by [`Xor::crack`],
+/// shorter columns are too short to be solved.
+#[cfg(feature = "analysis")]
+const MIN_COLUMN_LENGTH: usize = 8;
+
+/// The number of key sizes with the smallest hamming distance that are solved by [`Xor::crack`].
+#[cfg(feature = "analysis")]
+const KEY_SIZE_CANDIDATES: usize = 4;
+
+/// How much lower than the best score the score of a shorter key can be in [`Xor::crack`].
+#[cfg(feature = "analysis")]
+const SCORE_TOLERANCE: f64 = 0.02;
+
+/// The key and plaintext recovered by [`Xor::crack`]
+///
+/// The score is the [`english_score`] of the plaintext, the higher the better.
+#[cfg(feature = "analysis")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct XorSolution {
+    pub key: Vec<u8>,
+    pub plaintext: Vec<u8>,
+    pub score: f64,
+}
 
 /// Xor Cipher
 ///
 /// The struct is generated through the new() function.
 ///
-pub struct Xor<'a> {
-    key: &'a str,
+pub struct Xor {
+    key: Vec<u8>,
 }
 
-impl Xor<'_> {
+impl Xor {
     /// Initialize a xor cipher with the key.
     ///
     /// # Examples:
+    /// - Initialization with a text key:
     /// ```
     /// use cienli::ciphers::xor::Xor;
-    /// let xor = Xor::new("VMMN8");
+    /// let xor = Xor::new("VMMN8").unwrap();
     /// ```
-    pub fn new(key: &str) -> Xor {
-        Xor { key }
+    ///
+    /// - Initialization with a binary key:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// let xor = Xor::new(vec![0x00, 0xff, 0x7f]).unwrap();
+    /// ```
+    ///
+    /// # Errors:
+    /// The key must not be empty.
+    pub fn new<K: AsRef<[u8]>>(key: K) -> Result<Xor, Error> {
+        let key = key.as_ref();
+
+        if key.is_empty() {
+            return Err(Error::EmptyKey);
+        }
+
+        Ok(Xor { key: key.to_vec() })
     }
 
     /// Enciphers a message with the xor cipher.
@@ -25,14 +74,12 @@ impl Xor<'_> {
     /// # Examples:
     /// ```
     /// use cienli::ciphers::xor::Xor;
-    /// let xor = Xor::new("VMMN8");
+    /// let xor = Xor::new("VMMN8").unwrap();
     ///
-    /// assert_eq!("<=|zv", xor.encipher("jp14N"));
+    /// assert_eq!(b"<=|zv".to_vec(), xor.encipher("jp14N"));
     /// ```
-    pub fn encipher(&self, message: &str) -> String {
-        let key = key_gen(self.key, message.len()).unwrap();
-
-        Xor::xor_engine(message, &key)
+    pub fn encipher<M: AsRef<[u8]>>(&self, message: M) -> Vec<u8> {
+        self.xor_engine(message.as_ref())
     }
 
     /// Deciphers a cipher with the xor cipher.
@@ -40,44 +87,338 @@ impl Xor<'_> {
     /// # Examples:
     /// ```
     /// use cienli::ciphers::xor::Xor;
-    /// let xor = Xor::new("VMMN8");
-    /// assert_eq!("jp14N", xor.decipher("<=|zv"));
+    /// let xor = Xor::new("VMMN8").unwrap();
+    ///
+    /// assert_eq!(b"jp14N".to_vec(), xor.decipher("<=|zv"));
+    /// ```
+    pub fn decipher<C: AsRef<[u8]>>(&self, cipher: C) -> Vec<u8> {
+        self.xor_engine(cipher.as_ref())
+    }
+
+    /// Enciphers a message with the xor cipher and renders the cipher as hexadecimal.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// let xor = Xor::new("key").unwrap();
+    ///
+    /// assert_eq!("23a6d012", xor.encipher_hex("Héy"));
+    /// ```
+    pub fn encipher_hex<M: AsRef<[u8]>>(&self, message: M) -> String {
+        hex_encode(&self.encipher(message))
+    }
+
+    /// Parses a hexadecimal cipher and deciphers it with the xor cipher.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// let xor = Xor::new("key").unwrap();
+    ///
+    /// assert_eq!("Héy".as_bytes(), xor.decipher_hex("23a6d012").unwrap());
     /// ```
-    pub fn decipher(&self, cipher: &str) -> String {
-        let key = key_gen(self.key, cipher.len()).unwrap();
+    ///
+    /// # Errors:
+    /// The cipher must be a valid hexadecimal string.
+    pub fn decipher_hex(&self, cipher: &str) -> Result<Vec<u8>, Error> {
+        Ok(self.decipher(hex_decode(cipher)?))
+    }
 
-        Xor::xor_engine(cipher, &key)
+    /// Enciphers a message with the xor cipher and renders the cipher as base64.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// let xor = Xor::new("key").unwrap();
+    ///
+    /// assert_eq!("I6bQEg==", xor.encipher_base64("Héy"));
+    /// ```
+    pub fn encipher_base64<M: AsRef<[u8]>>(&self, message: M) -> String {
+        base64_encode(&self.encipher(message))
     }
 
-    fn xor_engine(message: &str, key: &str) -> String {
-        let mut result: String = String::new();
+    /// Parses a base64 cipher and deciphers it with the xor cipher.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// let xor = Xor::new("key").unwrap();
+    ///
+    /// assert_eq!("Héy".as_bytes(), xor.decipher_base64("I6bQEg==").unwrap());
+    /// ```
+    ///
+    /// # Errors:
+    /// The cipher must be a valid base64 string.
+    pub fn decipher_base64(&self, cipher: &str) -> Result<Vec<u8>, Error> {
+        Ok(self.decipher(base64_decode(cipher)?))
+    }
 
-        let message = message.as_bytes();
-        let key = key.as_bytes();
+    /// Recovers the key of an english cipher enciphered with a repeating key.
+    ///
+    /// The key sizes with the smallest normalized hamming distance between their blocks and their
+    /// divisors are tried, the cipher is split in one column per key byte and every column is solved as a single-byte
+    /// xor. Returns None if the cipher is empty.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// let xor = Xor::new("ICE").unwrap();
+    /// let cipher = xor.encipher(
+    ///     "Burning 'em, if you ain't quick and nimble\n\
+    ///     I go crazy when I hear a cymbal and a hi-hat with a souped up tempo\n\
+    ///     I'm on a roll, it's time to go solo, rollin' in my five point oh",
+    /// );
+    ///
+    /// let solution = Xor::crack(&cipher).unwrap();
+    /// assert_eq!(b"ICE".to_vec(), solution.key);
+    /// assert!(solution.plaintext.starts_with(b"Burning 'em"));
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn crack<C: AsRef<[u8]>>(cipher: C) -> Option<XorSolution> {
+        let cipher = cipher.as_ref();
 
-        for indx in 0..message.len() {
-            result.push((message[indx] ^ key[indx]) as char)
+        if cipher.is_empty() {
+            return None;
         }
 
-        result
+        let mut key_sizes = (1..=(cipher.len() / MIN_COLUMN_LENGTH).clamp(1, MAX_KEY_SIZE))
+            .map(|size| (size, normalized_distance(cipher, size)))
+            .collect::<Vec<(usize, f64)>>();
+        key_sizes.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        // The multiples of the key size have the same distance, so their divisors are tried too.
+        let mut candidates = key_sizes
+            .iter()
+            .take(KEY_SIZE_CANDIDATES)
+            .flat_map(|(size, _)| (1..=*size).filter(move |divisor| size % divisor == 0))
+            .collect::<Vec<usize>>();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let solutions = candidates
+            .into_iter()
+            .map(|size| {
+                let key = shortest_period(
+                    (0..size)
+                        .map(|column| solve_single_byte(cipher, column, size))
+                        .collect(),
+                );
+                let plaintext = Xor { key: key.clone() }.decipher(cipher);
+
+                XorSolution {
+                    score: english_score(&as_latin1(&plaintext)),
+                    key,
+                    plaintext,
+                }
+            })
+            .collect::<Vec<XorSolution>>();
+
+        // A longer key fits the noise of its shorter columns, so the shortest key that
+        // scores about as well as the best one wins.
+        let best_score = solutions
+            .iter()
+            .map(|solution| solution.score)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        solutions
+            .into_iter()
+            .filter(|solution| solution.score >= best_score - SCORE_TOLERANCE)
+            .min_by_key(|solution| solution.key.len())
+    }
+
+    fn xor_engine(&self, data: &[u8]) -> Vec<u8> {
+        data.iter()
+            .zip(self.key.iter().cycle())
+            .map(|(byte, key)| byte ^ key)
+            .collect()
+    }
+}
+
+/// Returns the average number of differing bits between the consecutive blocks of a key size,
+/// divided by the key size.
+#[cfg(feature = "analysis")]
+fn normalized_distance(cipher: &[u8], key_size: usize) -> f64 {
+    let blocks = cipher.chunks_exact(key_size).collect::<Vec<&[u8]>>();
+
+    if blocks.len() < 2 {
+        return f64::INFINITY;
+    }
+
+    let distance = blocks
+        .windows(2)
+        .map(|pair| {
+            pair[0]
+                .iter()
+                .zip(pair[1])
+                .map(|(a, b)| (a ^ b).count_ones() as usize)
+                .sum::<usize>()
+        })
+        .sum::<usize>();
+
+    distance as f64 / (blocks.len() - 1) as f64 / key_size as f64
+}
+
+/// Returns the key byte of a column whose deciphered bytes look the most like english.
+#[cfg(feature = "analysis")]
+fn solve_single_byte(cipher: &[u8], column: usize, key_size: usize) -> u8 {
+    let column = cipher
+        .iter()
+        .skip(column)
+        .step_by(key_size)
+        .copied()
+        .collect::<Vec<u8>>();
+
+    (0..=u8::MAX)
+        .map(|key| {
+            let plaintext = column.iter().map(|byte| byte ^ key).collect::<Vec<u8>>();
+            (key, english_score(&as_latin1(&plaintext)))
+        })
+        .fold((0, f64::NEG_INFINITY), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .0
+}
+
+/// Returns the shortest key that repeats into the given key (e.g. "ICE" for "ICEICE").
+#[cfg(feature = "analysis")]
+fn shortest_period(key: Vec<u8>) -> Vec<u8> {
+    (1..key.len())
+        .find(|period| {
+            key.len() % *period == 0
+                && key
+                    .iter()
+                    .zip(key.iter().skip(*period))
+                    .all(|(a, b)| a == b)
+        })
+        .map(|period| key[..period].to_vec())
+        .unwrap_or(key)
+}
+
+/// Maps every byte to a character, so the bytes can be scored without being valid UTF-8.
+#[cfg(feature = "analysis")]
+fn as_latin1(data: &[u8]) -> String {
+    data.iter().map(|byte| *byte as char).collect()
+}
+
+/// The text interface renders the cipher as hexadecimal, deciphering fails
+/// if the deciphered bytes are not valid UTF-8.
+impl Cipher for Xor {
+    fn encipher(&self, message: &str) -> Result<String, Error> {
+        Ok(self.encipher_hex(message))
+    }
+
+    fn decipher(&self, cipher: &str) -> Result<String, Error> {
+        String::from_utf8(self.decipher_hex(cipher)?).map_err(|error| Error::MalformedCiphertext {
+            position: error.utf8_error().valid_up_to() * 2,
+        })
+    }
+}
+
+impl StreamCipher for Xor {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        position: &mut usize,
+        _direction: Direction,
+    ) -> usize {
+        output.extend(input.iter().map(|byte| {
+            let key = self.key[*position % self.key.len()];
+            *position += 1;
+            byte ^ key
+        }));
+
+        input.len()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Xor;
+    use crate::ciphers::Cipher;
+    use crate::stream::{CipherReader, Direction};
+    use crate::Error;
+    use std::io::Read;
 
     #[test]
     fn encipher() {
-        let xor = Xor::new("VMMN8");
+        let xor = Xor::new("VMMN8").unwrap();
 
-        assert_eq!("<=|zv", xor.encipher("jp14N"));
+        assert_eq!(b"<=|zv".to_vec(), xor.encipher("jp14N"));
     }
 
     #[test]
     fn decipher() {
-        let xor = Xor::new("VMMN8");
+        let xor = Xor::new("VMMN8").unwrap();
+
+        assert_eq!(b"jp14N".to_vec(), xor.decipher("<=|zv"));
+    }
+
+    #[test]
+    fn binary_data() {
+        let xor = Xor::new([0xff, 0x00]).unwrap();
+        let data = [0x00, 0x80, 0xff, 0x7f, 0x01];
 
-        assert_eq!("jp14N", xor.decipher("<=|zv"));
+        assert_eq!(vec![0xff, 0x80, 0x00, 0x7f, 0xfe], xor.encipher(data));
+        assert_eq!(data.to_vec(), xor.decipher(xor.encipher(data)));
+    }
+
+    #[test]
+    fn non_ascii_round_trip() {
+        let xor = Xor::new("ключ").unwrap();
+        let message = "Привет, мир! • 😀";
+
+        assert_eq!(
+            message.as_bytes(),
+            xor.decipher_hex(&xor.encipher_hex(message)).unwrap()
+        );
+        assert_eq!(
+            message.as_bytes(),
+            xor.decipher_base64(&xor.encipher_base64(message)).unwrap()
+        );
+    }
+
+    #[test]
+    fn cipher_trait() {
+        let xor = Xor::new("VMMN8").unwrap();
+
+        assert_eq!("3c3d7c7a76", Cipher::encipher(&xor, "jp14N").unwrap());
+        assert_eq!("jp14N", Cipher::decipher(&xor, "3c3d7c7a76").unwrap());
+        assert!(Cipher::decipher(&xor, "a9").is_err());
+    }
+
+    #[test]
+    fn stream_reader() {
+        let xor = Xor::new("VMMN8").unwrap();
+        let data = (0..=255).cycle().take(100_000).collect::<Vec<u8>>();
+
+        let mut reader = CipherReader::new(&data[..], &xor, Direction::Encipher);
+        let mut cipher = Vec::new();
+        reader.read_to_end(&mut cipher).unwrap();
+
+        assert_eq!(xor.encipher(&data), cipher);
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn crack() {
+        let plaintext = "The harbour master kept a ledger of every boat that left before dawn, \
+            and in the winter he wrote down the weather beside each name, so that anyone who read \
+            the pages later could tell which crews had gone out in fog and which had waited.";
+        let xor = Xor::new([0x13, 0x37, 0xc0, 0xde, 0x42, 0x99, 0x07]).unwrap();
+
+        let solution = Xor::crack(xor.encipher(plaintext)).unwrap();
+        assert_eq!(vec![0x13, 0x37, 0xc0, 0xde, 0x42, 0x99, 0x07], solution.key);
+        assert_eq!(plaintext.as_bytes(), solution.plaintext);
+        assert_eq!(None, Xor::crack(""));
+    }
+
+    #[test]
+    fn empty_key() {
+        assert_eq!(Some(Error::EmptyKey), Xor::new("").err());
     }
 }
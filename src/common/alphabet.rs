@@ -0,0 +1,245 @@
+use crate::Error;
+
+/// Letter case of a character in an alphabet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+/// An ordered set of letters used by the substitution ciphers
+///
+/// Every letter has an uppercase and a lowercase form, the position of a letter
+/// in the alphabet is its numeric value and the length of the alphabet is the modulus.
+///
+/// The struct is generated through the new() function or one of the predefined alphabets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    upper: Vec<char>,
+    lower: Vec<char>,
+}
+
+impl Alphabet {
+    /// Initialize an alphabet with the uppercase and lowercase forms of its letters.
+    ///
+    /// # Examples:
+    /// ```
+    /// use cienli::common::Alphabet;
+    /// let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789", "abcdefghijklmnopqrstuvwxyz0123456789");
+    ///
+    /// assert!(alphabet.is_ok());
+    /// ```
+    ///
+    /// # Errors:
+    /// Both forms must have the same length, and a letter must not appear twice in the alphabet.
+    pub fn new(upper: &str, lower: &str) -> Result<Alphabet, Error> {
+        let upper = upper.chars().collect::<Vec<char>>();
+        let lower = lower.chars().collect::<Vec<char>>();
+
+        if upper.is_empty() {
+            return Err(Error::InvalidKey("Alphabet cannot be empty"));
+        }
+        if upper.len() != lower.len() {
+            return Err(Error::InvalidKey(
+                "Uppercase and lowercase letters must have the same length",
+            ));
+        }
+
+        for (index, letters) in upper.iter().zip(lower.iter()).enumerate() {
+            let duplicated = upper
+                .iter()
+                .zip(lower.iter())
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .any(|(_, other)| {
+                    [*other.0, *other.1].contains(letters.0)
+                        || [*other.0, *other.1].contains(letters.1)
+                });
+
+            if duplicated {
+                return Err(Error::InvalidKey(
+                    "Alphabet cannot contain repeated letters",
+                ));
+            }
+        }
+
+        Ok(Alphabet { upper, lower })
+    }
+
+    /// Initialize an alphabet whose letters have no case.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::common::Alphabet;
+    /// let alphabet = Alphabet::caseless("0123456789").unwrap();
+    ///
+    /// assert_eq!(10, alphabet.len());
+    /// ```
+    pub fn caseless(letters: &str) -> Result<Alphabet, Error> {
+        Alphabet::new(letters, letters)
+    }
+
+    /// The 26 letters of the latin alphabet.
+    pub fn latin() -> Alphabet {
+        Alphabet::predefined("ABCDEFGHIJKLMNOPQRSTUVWXYZ", "abcdefghijklmnopqrstuvwxyz")
+    }
+
+    /// The 26 letters of the latin alphabet followed by the 10 digits.
+    pub fn alphanumeric() -> Alphabet {
+        Alphabet::predefined(
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+            "abcdefghijklmnopqrstuvwxyz0123456789",
+        )
+    }
+
+    /// The 30 letters of the german alphabet, umlauts and eszett included.
+    pub fn german() -> Alphabet {
+        Alphabet::predefined(
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÜẞ",
+            "abcdefghijklmnopqrstuvwxyzäöüß",
+        )
+    }
+
+    /// The 33 letters of the russian cyrillic alphabet.
+    pub fn cyrillic() -> Alphabet {
+        Alphabet::predefined(
+            "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ",
+            "абвгдеёжзийклмнопрстуфхцчшщъыьэюя",
+        )
+    }
+
+    /// The 24 letters of the greek alphabet.
+    ///
+    /// **Note:** The final sigma (ς) is not part of the alphabet.
+    pub fn greek() -> Alphabet {
+        Alphabet::predefined("ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ", "αβγδεζηθικλμνξοπρστυφχψω")
+    }
+
+    /// The 22 letters of the hebrew alphabet.
+    ///
+    /// **Note:** The final forms of the letters are not part of the alphabet.
+    pub fn hebrew() -> Alphabet {
+        Alphabet::predefined("אבגדהוזחטיכלמנסעפצקרשת", "אבגדהוזחטיכלמנסעפצקרשת")
+    }
+
+    /// Returns the number of letters of the alphabet, which is also its modulus.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::common::Alphabet;
+    ///
+    /// assert_eq!(26, Alphabet::latin().len());
+    /// assert_eq!(33, Alphabet::cyrillic().len());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.upper.len()
+    }
+
+    /// Returns true if the alphabet has no letters.
+    pub fn is_empty(&self) -> bool {
+        self.upper.is_empty()
+    }
+
+    /// Returns true if the character is a letter of the alphabet in any case.
+    pub fn contains(&self, character: char) -> bool {
+        self.index_of(character).is_some()
+    }
+
+    /// Returns the position of a character in the alphabet and its case.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::common::{Alphabet, Case};
+    /// let alphabet = Alphabet::latin();
+    ///
+    /// assert_eq!(Some((2, Case::Lower)), alphabet.index_of('c'));
+    /// assert_eq!(None, alphabet.index_of('!'));
+    /// ```
+    pub fn index_of(&self, character: char) -> Option<(usize, Case)> {
+        if let Some(index) = self.upper.iter().position(|letter| *letter == character) {
+            return Some((index, Case::Upper));
+        }
+
+        self.lower
+            .iter()
+            .position(|letter| *letter == character)
+            .map(|index| (index, Case::Lower))
+    }
+
+    /// Returns the letter at a position of the alphabet in the given case,
+    /// the position wraps around the length of the alphabet.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::common::{Alphabet, Case};
+    /// let alphabet = Alphabet::latin();
+    ///
+    /// assert_eq!('c', alphabet.letter(28, Case::Lower));
+    /// ```
+    pub fn letter(&self, index: usize, case: Case) -> char {
+        match case {
+            Case::Upper => self.upper[index % self.len()],
+            Case::Lower => self.lower[index % self.len()],
+        }
+    }
+
+    fn predefined(upper: &str, lower: &str) -> Alphabet {
+        Alphabet {
+            upper: upper.chars().collect(),
+            lower: lower.chars().collect(),
+        }
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::latin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alphabet, Case};
+
+    #[test]
+    fn predefined_lengths_test() {
+        assert_eq!(26, Alphabet::latin().len());
+        assert_eq!(36, Alphabet::alphanumeric().len());
+        assert_eq!(30, Alphabet::german().len());
+        assert_eq!(33, Alphabet::cyrillic().len());
+        assert_eq!(24, Alphabet::greek().len());
+        assert_eq!(22, Alphabet::hebrew().len());
+    }
+
+    #[test]
+    fn predefined_are_valid_test() {
+        for alphabet in [
+            Alphabet::latin(),
+            Alphabet::alphanumeric(),
+            Alphabet::german(),
+            Alphabet::cyrillic(),
+            Alphabet::greek(),
+            Alphabet::hebrew(),
+        ] {
+            let upper = alphabet.upper.iter().collect::<String>();
+            let lower = alphabet.lower.iter().collect::<String>();
+            assert_eq!(Ok(alphabet), Alphabet::new(&upper, &lower));
+        }
+    }
+
+    #[test]
+    fn case_mapping_test() {
+        let alphabet = Alphabet::cyrillic();
+
+        assert_eq!(Some((6, Case::Upper)), alphabet.index_of('Ё'));
+        assert_eq!('ё', alphabet.letter(6, Case::Lower));
+    }
+
+    #[test]
+    fn invalid_alphabet_test() {
+        assert!(Alphabet::new("", "").is_err());
+        assert!(Alphabet::new("ABC", "ab").is_err());
+        assert!(Alphabet::new("ABA", "aba").is_err());
+        assert!(Alphabet::new("AB", "aa").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::Error;
+
+const BASE64_TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as a lowercase hexadecimal string.
+///
+/// # Example:
+/// ```
+/// use cienli::common::hex_encode;
+///
+/// assert_eq!("6a70ff", hex_encode(&[0x6a, 0x70, 0xff]));
+/// ```
+pub fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes a hexadecimal string into bytes, both lowercase and uppercase digits are accepted.
+///
+/// # Example:
+/// ```
+/// use cienli::common::hex_decode;
+///
+/// assert_eq!(vec![0x6a, 0x70, 0xff], hex_decode("6a70FF").unwrap());
+/// ```
+///
+/// # Errors:
+/// The text must only contain an even number of hexadecimal digits.
+pub fn hex_decode(text: &str) -> Result<Vec<u8>, Error> {
+    let digits = text.chars().collect::<Vec<char>>();
+    let mut result = Vec::with_capacity(digits.len() / 2);
+
+    for (pair, chunk) in digits.chunks(2).enumerate() {
+        if chunk.len() != 2 {
+            return Err(Error::MalformedCiphertext {
+                position: digits.len(),
+            });
+        }
+
+        let mut byte = 0;
+        for (offset, digit) in chunk.iter().enumerate() {
+            let value = digit.to_digit(16).ok_or(Error::MalformedCiphertext {
+                position: pair * 2 + offset,
+            })?;
+            byte = (byte << 4) | value as u8;
+        }
+        result.push(byte);
+    }
+
+    Ok(result)
+}
+
+/// Encodes bytes as a padded base64 string with the standard alphabet.
+///
+/// # Example:
+/// ```
+/// use cienli::common::base64_encode;
+///
+/// assert_eq!("SGVsbG8=", base64_encode(b"Hello"));
+/// ```
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity((data.len() + 2) / 3 * 4);
+
+    for chunk in data.chunks(3) {
+        let block = chunk.iter().enumerate().fold(0u32, |block, (index, byte)| {
+            block | (*byte as u32) << (16 - index * 8)
+        });
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (block >> (18 - index * 6)) & 0x3f;
+                result.push(BASE64_TABLE[sextet as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+
+    result
+}
+
+/// Decodes a base64 string with the standard alphabet into bytes, the padding is optional.
+///
+/// # Example:
+/// ```
+/// use cienli::common::base64_decode;
+///
+/// assert_eq!(b"Hello".to_vec(), base64_decode("SGVsbG8=").unwrap());
+/// ```
+///
+/// # Errors:
+/// The text must only contain characters of the base64 alphabet, followed by an optional padding
+/// that completes the last group of four characters.
+pub fn base64_decode(text: &str) -> Result<Vec<u8>, Error> {
+    let padding = text
+        .chars()
+        .rev()
+        .take_while(|character| *character == '=')
+        .count();
+    let characters = text.trim_end_matches('=').chars().collect::<Vec<char>>();
+    let mut result = Vec::with_capacity(characters.len() * 3 / 4);
+
+    if characters.len() % 4 == 1
+        || padding > 2
+        || (padding > 0 && (characters.len() + padding) % 4 != 0)
+    {
+        return Err(Error::MalformedCiphertext {
+            position: characters.len(),
+        });
+    }
+
+    for (group, chunk) in characters.chunks(4).enumerate() {
+        let mut block = 0u32;
+        for (offset, character) in chunk.iter().enumerate() {
+            let value = BASE64_TABLE
+                .iter()
+                .position(|symbol| *symbol as char == *character)
+                .ok_or(Error::MalformedCiphertext {
+                    position: group * 4 + offset,
+                })?;
+            block |= (value as u32) << (18 - offset * 6);
+        }
+
+        for index in 0..chunk.len() - 1 {
+            result.push((block >> (16 - index * 8)) as u8);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64_decode, base64_encode, hex_decode, hex_encode};
+    use crate::Error;
+
+    #[test]
+    fn hex_round_trip() {
+        let data = (0..=255).collect::<Vec<u8>>();
+
+        assert_eq!(data, hex_decode(&hex_encode(&data)).unwrap());
+    }
+
+    #[test]
+    fn hex_malformed() {
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 3 }),
+            hex_decode("6a7g")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 3 }),
+            hex_decode("6a7")
+        );
+    }
+
+    #[test]
+    fn base64_vectors() {
+        for (plain, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encoded, base64_encode(plain.as_bytes()));
+            assert_eq!(plain.as_bytes(), base64_decode(encoded).unwrap());
+        }
+    }
+
+    #[test]
+    fn base64_malformed() {
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 2 }),
+            base64_decode("Zm*v")
+        );
+        assert!(base64_decode("Zm9vY").is_err());
+        assert!(base64_decode("Zg===").is_err());
+        assert!(base64_decode("Zg=").is_err());
+    }
+
+    #[test]
+    fn base64_over_padded() {
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 4 }),
+            base64_decode("Zm9v=")
+        );
+        assert_eq!(
+            Err(Error::MalformedCiphertext { position: 4 }),
+            base64_decode("Zm9v==")
+        );
+        assert!(base64_decode("Zm8==").is_err());
+        assert_eq!(b"fo".to_vec(), base64_decode("Zm8").unwrap());
+    }
+}
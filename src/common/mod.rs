@@ -1,19 +1,19 @@
-pub fn key_gen(key: &str, message_len: usize) -> Result<String, &'static str> {
-    let mut result: String = String::from(key);
+mod alphabet;
+mod encoding;
+mod square;
 
+pub use alphabet::{Alphabet, Case};
+pub use encoding::{base64_decode, base64_encode, hex_decode, hex_encode};
+pub use square::{KeySquare, LetterMerge};
+
+use crate::Error;
+
+pub fn key_gen(key: &str, message_len: usize) -> Result<String, Error> {
     if key.is_empty() || message_len == 0 {
-        return Err("Error: Key and Message length must be 1 or greater than 1!!");
-    } else if key.len() == message_len {
-        return Ok(key.to_string());
-    } else if key.len() > message_len {
-        return Ok(key[..message_len].to_string());
-    } else if key.len() < message_len {
-        for left in 0..(message_len - key.len()) {
-            result.push(key.as_bytes()[left % key.len()] as char);
-        }
+        return Err(Error::EmptyKey);
     }
 
-    Ok(result)
+    Ok(key.chars().cycle().take(message_len).collect())
 }
 
 #[cfg(test)]
@@ -35,6 +35,11 @@ mod tests {
         assert_eq!(String::from("TEST"), key_gen("TEST", 4).unwrap());
     }
 
+    #[test]
+    fn non_ascii_key() {
+        assert_eq!(String::from("КЛЮЧКЛ"), key_gen("КЛЮЧ", 6).unwrap());
+    }
+
     #[test]
     fn too_small_value() {
         assert!(key_gen("TEST", 0).is_err());
@@ -0,0 +1,199 @@
+use crate::Error;
+
+/// How the 26 latin letters fit in the 25 cells of a 5x5 square
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterMerge {
+    /// 'J' is written as 'I'.
+    IJ,
+    /// 'K' is written as 'C'.
+    CK,
+    /// 'Q' is left out of the square and of the messages.
+    OmitQ,
+}
+
+impl LetterMerge {
+    /// Returns the letter of the square standing for an uppercase latin letter,
+    /// or None if the letter is left out.
+    fn apply(self, letter: char) -> Option<char> {
+        match (self, letter) {
+            (LetterMerge::IJ, 'J') => Some('I'),
+            (LetterMerge::CK, 'K') => Some('C'),
+            (LetterMerge::OmitQ, 'Q') => None,
+            _ => Some(letter),
+        }
+    }
+}
+
+/// A square of letters filled with a keyword followed by the rest of the alphabet
+///
+/// The struct is generated through the new() function for a 5x5 square of letters
+/// or the alphanumeric() function for a 6x6 square of letters and digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySquare {
+    size: usize,
+    cells: Vec<char>,
+    merge: Option<LetterMerge>,
+}
+
+impl KeySquare {
+    /// Initialize a 5x5 square with a keyword, the repeated letters of the keyword are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::common::{KeySquare, LetterMerge};
+    /// let square = KeySquare::new("PLAYFAIR EXAMPLE", LetterMerge::IJ).unwrap();
+    ///
+    /// assert_eq!(Some((0, 4)), square.position('F'));
+    /// assert_eq!(Some((1, 0)), square.position('j'));
+    /// assert_eq!('X', square.letter(1, 3));
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters and spaces.
+    pub fn new(keyword: &str, merge: LetterMerge) -> Result<KeySquare, Error> {
+        KeySquare::fill(keyword, 5, ('A'..='Z').collect(), Some(merge))
+    }
+
+    /// Initialize a 6x6 square of the latin letters and the digits with a keyword,
+    /// the repeated characters of the keyword are skipped.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::common::KeySquare;
+    /// let square = KeySquare::alphanumeric("1 privacy").unwrap();
+    ///
+    /// assert_eq!(Some((0, 0)), square.position('1'));
+    /// assert_eq!(Some((0, 3)), square.position('i'));
+    /// assert_eq!('9', square.letter(5, 5));
+    /// ```
+    ///
+    /// # Errors:
+    /// The keyword must only contain latin letters, digits and spaces.
+    pub fn alphanumeric(keyword: &str) -> Result<KeySquare, Error> {
+        KeySquare::fill(keyword, 6, ('A'..='Z').chain('0'..='9').collect(), None)
+    }
+
+    /// Returns the number of rows (and columns) of the square.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the row and the column of a character in the square, the letters are
+    /// case-insensitive and merged like the square. Returns None if the character
+    /// is not in the square.
+    pub fn position(&self, character: char) -> Option<(usize, usize)> {
+        let letter = self.normalize(character)?;
+
+        self.cells
+            .iter()
+            .position(|cell| *cell == letter)
+            .map(|index| (index / self.size, index % self.size))
+    }
+
+    /// Returns the letter of a cell of the square, the row and the column wrap around.
+    pub fn letter(&self, row: usize, column: usize) -> char {
+        self.cells[(row % self.size) * self.size + column % self.size]
+    }
+
+    /// Returns the letter of the square standing for a character, or None if
+    /// the character is not in the square.
+    pub fn normalize(&self, character: char) -> Option<char> {
+        let letter = character.to_ascii_uppercase();
+        let letter = match self.merge {
+            Some(merge) => merge.apply(letter)?,
+            None => letter,
+        };
+
+        match self.cells.contains(&letter) {
+            true => Some(letter),
+            false => None,
+        }
+    }
+
+    fn fill(
+        keyword: &str,
+        size: usize,
+        symbols: Vec<char>,
+        merge: Option<LetterMerge>,
+    ) -> Result<KeySquare, Error> {
+        let apply = |symbol: char| match merge {
+            Some(merge) => merge.apply(symbol),
+            None => Some(symbol),
+        };
+        let mut cells = Vec::with_capacity(size * size);
+
+        for character in keyword.chars().filter(|character| *character != ' ') {
+            let symbol = character.to_ascii_uppercase();
+            if !symbols.contains(&symbol) {
+                return Err(Error::UnsupportedCharacter(character));
+            }
+
+            if let Some(symbol) = apply(symbol) {
+                if !cells.contains(&symbol) {
+                    cells.push(symbol);
+                }
+            }
+        }
+
+        for symbol in symbols.into_iter().filter_map(apply) {
+            if !cells.contains(&symbol) {
+                cells.push(symbol);
+            }
+        }
+
+        Ok(KeySquare { size, cells, merge })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeySquare, LetterMerge};
+    use crate::Error;
+
+    #[test]
+    fn keyword_square() {
+        let square = KeySquare::new("Monarchy", LetterMerge::IJ).unwrap();
+        let rows = (0..5)
+            .map(|row| (0..5).map(|column| square.letter(row, column)).collect())
+            .collect::<Vec<String>>();
+
+        assert_eq!(vec!["MONAR", "CHYBD", "EFGIK", "LPQST", "UVWXZ"], rows);
+    }
+
+    #[test]
+    fn omit_q() {
+        let square = KeySquare::new("", LetterMerge::OmitQ).unwrap();
+
+        assert_eq!(None, square.position('Q'));
+        assert_eq!(Some((1, 4)), square.position('J'));
+        assert_eq!('Z', square.letter(4, 4));
+    }
+
+    #[test]
+    fn merge_c_k() {
+        let square = KeySquare::new("Kayak", LetterMerge::CK).unwrap();
+
+        assert_eq!(Some((0, 0)), square.position('k'));
+        assert_eq!(square.position('C'), square.position('K'));
+        assert_eq!('Z', square.letter(4, 4));
+    }
+
+    #[test]
+    fn alphanumeric_square() {
+        let square = KeySquare::alphanumeric("Agent 007").unwrap();
+
+        assert_eq!(6, square.size());
+        assert_eq!(Some((0, 5)), square.position('0'));
+        assert_eq!(Some((1, 0)), square.position('7'));
+        assert_eq!(None, square.position('#'));
+        assert!(KeySquare::alphanumeric("Agent_007").is_err());
+    }
+
+    #[test]
+    fn invalid_keyword() {
+        assert_eq!(
+            Some(Error::UnsupportedCharacter('1')),
+            KeySquare::new("key1", LetterMerge::IJ).err()
+        );
+    }
+}
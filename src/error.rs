@@ -0,0 +1,64 @@
+use std::fmt;
+
+/// Errors returned by the ciphers of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The key is not valid for the cipher, the reason is attached.
+    InvalidKey(&'static str),
+    /// The key is not co-prime with the modulus of the cipher.
+    KeyNotCoprime { key: usize, modulus: usize },
+    /// The key is empty.
+    EmptyKey,
+    /// The ciphertext cannot be deciphered, the position is the index of the
+    /// offending character.
+    MalformedCiphertext { position: usize },
+    /// The character is not supported by the cipher.
+    UnsupportedCharacter(char),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidKey(reason) => write!(f, "Invalid key: {}", reason),
+            Error::KeyNotCoprime { key, modulus } => {
+                write!(f, "The key {} is not co-prime with {}", key, modulus)
+            }
+            Error::EmptyKey => write!(f, "The key is empty"),
+            Error::MalformedCiphertext { position } => {
+                write!(f, "Malformed ciphertext at position {}", position)
+            }
+            Error::UnsupportedCharacter(character) => {
+                write!(f, "Unsupported character {:?}", character)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn display_test() {
+        assert_eq!(
+            "The key 10 is not co-prime with 26",
+            Error::KeyNotCoprime {
+                key: 10,
+                modulus: 26
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "Malformed ciphertext at position 3",
+            Error::MalformedCiphertext { position: 3 }.to_string()
+        );
+    }
+
+    #[test]
+    fn std_error_test() {
+        let error: Box<dyn std::error::Error> = Box::new(Error::EmptyKey);
+        assert_eq!("The key is empty", error.to_string());
+    }
+}
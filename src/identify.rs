@@ -0,0 +1,515 @@
+//! Identification of the cipher which produced a ciphertext.
+//!
+//! The ciphertext is described by a few [`Features`] (its character set, the index of
+//! coincidence and the periodicity of its letters, ...), then every family of ciphers of the
+//! crate gets a score from the features, the higher the more likely.
+use crate::analysis::{english_score, index_of_coincidence, letter_counts, ENGLISH_FREQUENCIES};
+use crate::common::{base64_decode, hex_decode, Alphabet};
+
+/// The index of coincidence above which the letters are considered monoalphabetic.
+const MONOALPHABETIC_IOC: f64 = 0.055;
+
+/// The index of coincidence of english letters.
+const ENGLISH_IOC: f64 = 0.066;
+
+/// The index of coincidence of uniformly random letters.
+const RANDOM_IOC: f64 = 1.0 / 26.0;
+
+/// The index of coincidence of the columns of a periodic text.
+const PERIODIC_IOC: f64 = 0.06;
+
+/// The longest period looked for.
+const MAX_PERIOD: usize = 20;
+
+/// The fewest letters of a column of a periodic text.
+const MIN_COLUMN_LENGTH: usize = 6;
+
+/// Families of ciphers told apart by the identification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CipherKind {
+    /// Columnar, rail fence or scytale transposition, the letters keep their frequencies.
+    Transposition,
+    /// Caesar cipher or ROT13.
+    Caesar,
+    Atbash,
+    Affine,
+    /// Simple substitution with any mixed alphabet.
+    Substitution,
+    /// Vigenère, Beaufort or Gronsfeld cipher, with a repeating key.
+    Vigenere,
+    Autokey,
+    Enigma,
+    Hill,
+    Playfair,
+    Bifid,
+    Trifid,
+    PolybiusSquare,
+    Adfgvx,
+    Bacon,
+    Rot47,
+    /// XOR cipher, encoded as hex or base64.
+    Xor,
+}
+
+/// A guess of the identification
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guess {
+    pub cipher: CipherKind,
+    pub score: f64,
+}
+
+/// Features of a ciphertext
+///
+/// The whitespace is not part of the ciphertext, and the letters are the latin letters
+/// counted case-insensitively.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Features {
+    /// The number of characters.
+    pub length: usize,
+    /// The distinct characters, sorted.
+    pub symbols: Vec<char>,
+    /// The number of letters.
+    pub letters: usize,
+    /// The index of coincidence of the letters.
+    pub index_of_coincidence: f64,
+    /// The smallest period, up to 20, whose columns of at least 6 letters have the index of
+    /// coincidence of a monoalphabetic text, 1 for a monoalphabetic text.
+    pub period: Option<usize>,
+    /// Whether two letters of a digraph (the letters in pairs from the first one) are the same.
+    pub doubled_digraph: bool,
+}
+
+impl Features {
+    /// Computes the features of a ciphertext.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::identify::Features;
+    ///
+    /// let features = Features::new("AABAB BABBA");
+    ///
+    /// assert_eq!(10, features.length);
+    /// assert_eq!(vec!['A', 'B'], features.symbols);
+    /// assert!(features.doubled_digraph);
+    /// ```
+    pub fn new(cipher: &str) -> Features {
+        let alphabet = Alphabet::latin();
+        let compact = cipher
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .collect::<String>();
+
+        let mut symbols = compact.chars().collect::<Vec<char>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        let letters = compact
+            .chars()
+            .filter_map(|character| alphabet.index_of(character))
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>();
+
+        Features {
+            length: compact.chars().count(),
+            symbols,
+            letters: letters.len(),
+            index_of_coincidence: index_of_coincidence(&compact, &alphabet),
+            period: Features::period(&letters),
+            doubled_digraph: letters.chunks_exact(2).any(|pair| pair[0] == pair[1]),
+        }
+    }
+
+    /// Returns the smallest period whose columns have a monoalphabetic index of coincidence.
+    fn period(letters: &[usize]) -> Option<usize> {
+        (1..=MAX_PERIOD)
+            .take_while(|period| letters.len() >= MIN_COLUMN_LENGTH * period)
+            .find(|period| {
+                let total = (0..*period)
+                    .map(|column| {
+                        let mut counts = [0usize; 26];
+                        for letter in letters.iter().skip(column).step_by(*period) {
+                            counts[*letter] += 1;
+                        }
+                        coincidence(&counts)
+                    })
+                    .sum::<f64>();
+
+                let threshold = match period {
+                    1 => MONOALPHABETIC_IOC,
+                    _ => PERIODIC_IOC,
+                };
+                total / *period as f64 >= threshold
+            })
+    }
+
+    /// Returns the number of alphanumeric characters if they are only two symbols,
+    /// case-insensitively.
+    fn two_symbols(&self, cipher: &str) -> Option<usize> {
+        let mut symbols = self
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.is_alphanumeric())
+            .flat_map(|symbol| symbol.to_lowercase())
+            .collect::<Vec<char>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        let count = cipher
+            .chars()
+            .filter(|character| character.is_alphanumeric())
+            .count();
+        match symbols.len() == 2 && count * 2 >= self.length {
+            true => Some(count),
+            false => None,
+        }
+    }
+
+    /// Returns whether every symbol is in the set, case-insensitively.
+    fn only(&self, set: &str) -> bool {
+        self.symbols
+            .iter()
+            .all(|symbol| set.chars().any(|other| other.eq_ignore_ascii_case(symbol)))
+    }
+}
+
+/// Returns the possible ciphers of a ciphertext, from the most likely to the least likely.
+///
+/// The scores range from 0 to 1, the ciphers with a score of zero are left out.
+///
+/// # Examples:
+/// - A Polybius square ciphertext:
+/// ```
+/// use cienli::identify::{identify, CipherKind};
+///
+/// let guesses = identify("2315313134 52344231 14");
+/// assert_eq!(CipherKind::PolybiusSquare, guesses[0].cipher);
+/// ```
+///
+/// - A Caesar ciphertext:
+/// ```
+/// use cienli::identify::{identify, CipherKind};
+///
+/// let guesses = identify(
+///     "Wkh vwrub zdv vr vwurqj wkdw wkh vkls frxog qrw uhdfk wkh kduerxu ehiruh qljkw, \
+///     vr wkh fdswdlq ghflghg wr zdlw iru wkh pruqlqj wlgh.",
+/// );
+/// assert_eq!(CipherKind::Caesar, guesses[0].cipher);
+/// ```
+pub fn identify(cipher: &str) -> Vec<Guess> {
+    let features = Features::new(cipher);
+    let mut guesses = Vec::new();
+
+    if features.length == 0 {
+        return guesses;
+    }
+
+    if let Some(count) = features.two_symbols(cipher) {
+        let score = match count % 5 {
+            0 => 0.95,
+            _ => 0.6,
+        };
+        guesses.push(Guess {
+            cipher: CipherKind::Bacon,
+            score,
+        });
+        return guesses;
+    }
+
+    let digits = cipher
+        .chars()
+        .filter(|character| character.is_ascii_digit())
+        .collect::<Vec<char>>();
+    if features.letters == 0 && digits.len() * 5 >= features.length * 4 {
+        let square = digits.iter().all(|digit| ('1'..='6').contains(digit));
+        let score = match (square, digits.len() % 2) {
+            (true, 0) => 0.9,
+            (true, _) => 0.4,
+            _ => 0.0,
+        };
+        guesses.push(Guess {
+            cipher: CipherKind::PolybiusSquare,
+            score,
+        });
+    }
+
+    if features.only("ADFGVX") {
+        let score = match features.length % 2 {
+            0 => 0.9,
+            _ => 0.4,
+        };
+        guesses.push(Guess {
+            cipher: CipherKind::Adfgvx,
+            score,
+        });
+        return ranked(guesses);
+    }
+
+    if !cipher.contains(char::is_whitespace) {
+        let mixed = features.symbols.iter().any(char::is_ascii_lowercase)
+            && features.symbols.iter().any(char::is_ascii_uppercase)
+            && features
+                .symbols
+                .iter()
+                .any(|symbol| !symbol.is_ascii_alphabetic());
+        // Hex of letters only (e.g. "deadbeef") is as likely to be a word as an encoding.
+        let digits = features.letters < features.length;
+        let score = match (hex_decode(cipher), base64_decode(cipher)) {
+            (Ok(_), _) if features.letters > 0 && digits => 0.9,
+            (Ok(_), _) => 0.5,
+            (_, Ok(_)) if mixed && features.length % 4 == 0 => 0.9,
+            (_, Ok(_)) if mixed => 0.6,
+            _ => 0.0,
+        };
+        guesses.push(Guess {
+            cipher: CipherKind::Xor,
+            score,
+        });
+    }
+
+    if guesses.iter().any(|guess| guess.score >= 0.9) {
+        return ranked(guesses);
+    }
+
+    let punctuation = cipher
+        .chars()
+        .filter(|character| character.is_ascii_punctuation())
+        .count();
+    let rot47 = cipher.chars().map(rot47).collect::<String>();
+    if punctuation * 10 >= features.length && english_score(&rot47) > english_score(cipher) {
+        guesses.push(Guess {
+            cipher: CipherKind::Rot47,
+            score: 0.8,
+        });
+    }
+
+    if features.letters * 2 >= features.length && features.letters >= 2 {
+        letter_guesses(cipher, &features, &mut guesses);
+    }
+
+    ranked(guesses)
+}
+
+/// Scores the ciphers of letters.
+fn letter_guesses(cipher: &str, features: &Features, guesses: &mut Vec<Guess>) {
+    let monoalphabetic =
+        ((features.index_of_coincidence - RANDOM_IOC) / (ENGLISH_IOC - RANDOM_IOC)).clamp(0.0, 1.0);
+    let polyalphabetic = 1.0 - monoalphabetic;
+    let mut push = |cipher: CipherKind, score: f64| guesses.push(Guess { cipher, score });
+
+    let counts = letter_counts(cipher, &Alphabet::latin());
+    let (multiplier, shift, distance) = best_affine_key(&counts);
+    let fits = distance < 50.0 + features.letters as f64 / 2.0;
+
+    match (fits, multiplier, shift) {
+        (true, 1, 0) => push(CipherKind::Transposition, monoalphabetic),
+        (true, 1, _) => push(CipherKind::Caesar, monoalphabetic),
+        (true, 25, 25) => push(CipherKind::Atbash, monoalphabetic),
+        (true, _, _) => push(CipherKind::Affine, monoalphabetic),
+        (false, _, _) => push(CipherKind::Substitution, monoalphabetic),
+    }
+    if fits {
+        push(CipherKind::Substitution, monoalphabetic / 2.0);
+    }
+
+    match features.period {
+        Some(period) if period > 1 => push(CipherKind::Vigenere, polyalphabetic),
+        _ => {
+            let even = match features.letters % 2 {
+                0 => 1.0,
+                _ => 0.4,
+            };
+            push(CipherKind::Autokey, 0.6 * polyalphabetic);
+            push(CipherKind::Enigma, 0.5 * polyalphabetic);
+            push(CipherKind::Hill, 0.5 * even * polyalphabetic);
+        }
+    }
+
+    let letters = features.letters as f64;
+    let uppercase = features
+        .symbols
+        .iter()
+        .all(|symbol| symbol.is_ascii_uppercase() || *symbol == '+');
+    let without_j = counts[9] == 0;
+    if uppercase && features.symbols.contains(&'+') {
+        push(CipherKind::Trifid, 0.9);
+    } else if uppercase && without_j && features.letters % 2 == 0 && !features.doubled_digraph {
+        let chance = (25.0f64 / 26.0).powf(letters) * (24.0f64 / 25.0).powf(letters / 2.0);
+        push(CipherKind::Playfair, 0.95 * (1.0 - chance));
+    } else if uppercase && without_j {
+        let chance = (25.0f64 / 26.0).powf(letters);
+        push(CipherKind::Bifid, 0.95 * (1.0 - chance));
+    } else if uppercase {
+        push(CipherKind::Trifid, 0.7 * polyalphabetic);
+    }
+}
+
+/// Returns the affine key (the multiplier and the shift) which best maps english letter
+/// frequencies onto the counts, and its chi-squared distance.
+fn best_affine_key(counts: &[usize]) -> (usize, usize, f64) {
+    let total = counts.iter().sum::<usize>() as f64;
+    let mut best = (1, 0, f64::INFINITY);
+
+    for multiplier in (1..26).step_by(2).filter(|multiplier| *multiplier != 13) {
+        for shift in 0..26 {
+            let distance = ENGLISH_FREQUENCIES
+                .iter()
+                .enumerate()
+                .map(|(letter, frequency)| {
+                    let expected = total * frequency;
+                    let count = counts[(multiplier * letter + shift) % 26] as f64;
+                    (count - expected).powi(2) / expected
+                })
+                .sum::<f64>();
+
+            if distance < best.2 {
+                best = (multiplier, shift, distance);
+            }
+        }
+    }
+
+    best
+}
+
+fn coincidence(counts: &[usize]) -> f64 {
+    let total = counts.iter().sum::<usize>();
+    if total < 2 {
+        return 0.0;
+    }
+
+    let coincidences = counts
+        .iter()
+        .map(|count| count * count.saturating_sub(1))
+        .sum::<usize>();
+    coincidences as f64 / (total * (total - 1)) as f64
+}
+
+fn rot47(character: char) -> char {
+    match character {
+        '!'..='~' => (((character as u8 - 33 + 47) % 94) + 33) as char,
+        _ => character,
+    }
+}
+
+fn ranked(mut guesses: Vec<Guess>) -> Vec<Guess> {
+    guesses.retain(|guess| guess.score > 0.0);
+    guesses.sort_by(|a, b| b.score.total_cmp(&a.score));
+    guesses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{identify, CipherKind, Features};
+
+    const MESSAGE: &str = "The history of secret writing is as old as writing itself, and for \
+        most of that time the ciphers were simple enough to be worked by hand with a pencil and \
+        a sheet of paper. Messengers carried them across mountains and seas, and the generals \
+        who received them trusted that no enemy would read them on the way.";
+
+    fn top(cipher: &str) -> CipherKind {
+        identify(cipher)[0].cipher
+    }
+
+    #[test]
+    fn features_test() {
+        let features = Features::new("LXFOPV EFRNHR");
+
+        assert_eq!(12, features.length);
+        assert_eq!(12, features.letters);
+        assert!(!features.doubled_digraph);
+        assert_eq!(None, Features::new("").period);
+    }
+
+    #[test]
+    fn period_test() {
+        assert_eq!(Some(1), Features::new(MESSAGE).period);
+
+        let vigenere = "Elq vvdxafl zj espcif ketxubt tw mg bwh mg jcmfwar mfgrwj, mbq qsd abdx at \
+            gsef hvxi fvr nmbvrcw isep wuacwi qbbfkt hb mi icevip pl sezr jtxt o cprowy lrp o fsiqh \
+            bq tmdrc. Qqgfprssed gmfetip hupq mqezwe abfrfovyw mbq dimg, nyh fvr rizselpe kuz \
+            vqqrtzqr gsiy hefwfsq elmh az izszj aaiyo vqoq elqa by xts jlc.";
+        assert_eq!(Some(5), Features::new(vigenere).period);
+        assert_eq!(CipherKind::Vigenere, top(vigenere));
+    }
+
+    #[test]
+    fn encodings_test() {
+        assert_eq!(CipherKind::Bacon, top("AABBB AABAA ABABA ABABA ABBAB"));
+        assert_eq!(CipherKind::Bacon, top("aabbbaabaaababaababaabbab"));
+        assert_eq!(CipherKind::PolybiusSquare, top("2315313134"));
+        assert_eq!(CipherKind::Adfgvx, top("DGDDDAGDDGAFADDFDADVDVFAADVX"));
+        assert_eq!(CipherKind::Xor, top("23a6d012"));
+        assert_eq!(CipherKind::Rot47, top("• w6==@ uC:6?5 gb``_ iX •"));
+        assert_eq!(
+            CipherKind::Xor,
+            top("JgAcH0UUDkUYH0UNAwBZBAkdSwcLAgEeDkUYDREcGUUdChcSSwQXD0UbGQwXDEUNAwBZGwQJDhcK")
+        );
+    }
+
+    #[test]
+    fn hex_letters_test() {
+        for cipher in ["deadbeefcafe", "faceface"] {
+            let guesses = identify(cipher);
+            let xor = guesses
+                .iter()
+                .find(|guess| guess.cipher == CipherKind::Xor)
+                .unwrap();
+
+            assert!(xor.score < 0.9);
+            assert!(guesses.len() > 1);
+        }
+    }
+
+    #[test]
+    fn monoalphabetic_test() {
+        let caesar = MESSAGE
+            .chars()
+            .map(|character| match character {
+                'a'..='z' => (((character as u8 - b'a' + 7) % 26) + b'a') as char,
+                _ => character,
+            })
+            .collect::<String>();
+        let atbash = MESSAGE
+            .chars()
+            .map(|character| match character {
+                'a'..='z' => (b'z' - (character as u8 - b'a')) as char,
+                _ => character,
+            })
+            .collect::<String>();
+        let mut transposition = MESSAGE.chars().collect::<Vec<char>>();
+        transposition.reverse();
+
+        assert_eq!(CipherKind::Caesar, top(&caesar));
+        assert_eq!(CipherKind::Atbash, top(&atbash));
+        assert_eq!(
+            CipherKind::Transposition,
+            top(&transposition.into_iter().collect::<String>())
+        );
+    }
+
+    #[test]
+    fn fractionating_test() {
+        assert_eq!(
+            CipherKind::Playfair,
+            top("CLKLCLRSPDFMTCDAKBIFOILKDKRMIRRYDAGAKQCFSOLFAT")
+        );
+        assert_eq!(
+            CipherKind::Trifid,
+            top("FSXBEFSIFITEGAOCEAILRUOD++RUSOQGUHBZRSGPBZRKTC")
+        );
+        assert_eq!(
+            CipherKind::Bifid,
+            top(
+                "REOYRXNMEZEOWNNCOZEOWHXLPLVOYMYRXEYLSEQEOXDANFRIIEWQROQEYNDXONSAYCOELPOW\
+                HLRASYLMODXSAPOPANPRIOEXQYYLEORXRCYSYHANMWUSASYLSYWTSQTSOZHRWTBOZENLYTBO\
+                BRIAFSHTQGIBRGHGSRERTHEYDPGZOVVWNXIGOYQAXESTQNSRRRORHETVGROHRHLWWYXREAFS\
+                SEYEOSHTMFSTVTVGIOYRADXRXGRNGYA"
+            )
+        );
+    }
+
+    #[test]
+    fn empty_test() {
+        assert!(identify("").is_empty());
+        assert!(identify(" \n").is_empty());
+    }
+}
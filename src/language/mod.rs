@@ -0,0 +1,252 @@
+//! N-gram models of several languages, bundled with the crate.
+//!
+//! The module is behind the `language` feature, which is not a default feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cienli = { version = "0.4.0", features = ["language"]}
+//! ```
+//!
+//! The models use the latin alphabet, the texts are folded with [`fold_diacritics`] before
+//! training so that "Größe" is counted as "Groesse" and "été" as "ete", the way messages are
+//! usually written before being enciphered.
+//!
+//! The english models are the ones of [`NgramModel`]. The other models are saved with
+//! [`NgramModel::save`] in the `cienli-models` crate, they were trained the same way on texts of
+//! about 70 000 letters per language written for the crate and distributed under its MIT
+//! licence, and the `train_model` example regenerates them from the `cienli-models/corpus`
+//! directory of the repository.
+use crate::analysis::NgramModel;
+use std::sync::OnceLock;
+
+/// Languages with bundled models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+impl Language {
+    /// Every language with bundled models.
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+    ];
+
+    /// Returns the monogram model of the language, the frequencies of its letters.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::language::Language;
+    ///
+    /// let model = Language::German.monograms();
+    /// assert!(model.score("e") > model.score("q"));
+    /// ```
+    pub fn monograms(self) -> &'static NgramModel {
+        self.bundled(0)
+    }
+
+    /// Returns the bigram model of the language.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::language::Language;
+    ///
+    /// let model = Language::French.bigrams();
+    /// assert!(model.score("Bonjour") > model.score("Bjnouro"));
+    /// ```
+    pub fn bigrams(self) -> &'static NgramModel {
+        self.bundled(1)
+    }
+
+    /// Returns the quadgram model of the language.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::language::Language;
+    ///
+    /// let model = Language::Spanish.quadgrams();
+    /// assert!(model.score("Buenos dias") > model.score("Bneuos sida"));
+    /// ```
+    pub fn quadgrams(self) -> &'static NgramModel {
+        self.bundled(2)
+    }
+
+    /// Returns the language whose bigram model scores the text the highest.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::language::Language;
+    ///
+    /// assert_eq!(Language::German, Language::detect("Der Hund schläft unter dem Tisch"));
+    /// assert_eq!(Language::Spanish, Language::detect("El perro duerme debajo de la mesa"));
+    /// ```
+    pub fn detect(text: &str) -> Language {
+        let text = fold_diacritics(text);
+
+        Language::ALL
+            .iter()
+            .copied()
+            .map(|language| (language, language.bigrams().score(&text)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(language, _)| language)
+            .unwrap_or(Language::English)
+    }
+
+    /// Returns the monogram (0), bigram (1) or quadgram (2) model, loaded once on the first call.
+    fn bundled(self, order: usize) -> &'static NgramModel {
+        static ENGLISH_MONOGRAMS: OnceLock<NgramModel> = OnceLock::new();
+        static GERMAN: [OnceLock<NgramModel>; 3] =
+            [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+        static FRENCH: [OnceLock<NgramModel>; 3] =
+            [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+        static SPANISH: [OnceLock<NgramModel>; 3] =
+            [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+
+        let (models, saved) = match (self, order) {
+            (Language::English, 1) => return NgramModel::english_bigrams(),
+            (Language::English, 2) => return NgramModel::english_quadgrams(),
+            (Language::English, _) => {
+                return ENGLISH_MONOGRAMS.get_or_init(|| NgramModel::english(1))
+            }
+            (Language::German, _) => (&GERMAN, cienli_models::GERMAN),
+            (Language::French, _) => (&FRENCH, cienli_models::FRENCH),
+            (Language::Spanish, _) => (&SPANISH, cienli_models::SPANISH),
+        };
+
+        models[order]
+            .get_or_init(|| NgramModel::load(saved[order]).expect("The bundled models are valid"))
+    }
+}
+
+/// Replaces the accented letters of the languages by their latin letters, the german umlauts
+/// and ligatures are spelled out ("ä" to "ae", "ß" to "ss", "œ" to "oe") and the case is kept.
+///
+/// # Example:
+/// ```
+/// use cienli::language::fold_diacritics;
+///
+/// assert_eq!("Groesse, Ete, Espana", fold_diacritics("Größe, Été, España"));
+/// ```
+pub fn fold_diacritics(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        let lower = character.to_lowercase().next().unwrap_or(character);
+        let replacement = match lower {
+            'à' | 'á' | 'â' | 'ã' | 'å' => "a",
+            'ä' | 'æ' => "ae",
+            'ç' => "c",
+            'è' | 'é' | 'ê' | 'ë' => "e",
+            'ì' | 'í' | 'î' | 'ï' => "i",
+            'ñ' => "n",
+            'ò' | 'ó' | 'ô' | 'õ' => "o",
+            'ö' | 'œ' => "oe",
+            'ù' | 'ú' | 'û' => "u",
+            'ü' => "ue",
+            'ß' => "ss",
+            'ÿ' => "y",
+            _ => {
+                folded.push(character);
+                continue;
+            }
+        };
+
+        match character.is_uppercase() {
+            true => {
+                let mut letters = replacement.chars();
+                folded.extend(letters.next().map(|letter| letter.to_ascii_uppercase()));
+                folded.extend(letters);
+            }
+            false => folded.push_str(replacement),
+        }
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fold_diacritics, Language};
+
+    #[test]
+    fn bundled_models() {
+        for language in Language::ALL {
+            assert_eq!(1, language.monograms().n());
+            assert_eq!(2, language.bigrams().n());
+            assert_eq!(4, language.quadgrams().n());
+            assert!(std::ptr::eq(language.bigrams(), language.bigrams()));
+        }
+    }
+
+    #[test]
+    fn detect_test() {
+        let texts = [
+            (
+                Language::English,
+                "The weather was fine and the children played by the river",
+            ),
+            (
+                Language::German,
+                "Das Wetter war schön und die Kinder spielten am Fluss",
+            ),
+            (
+                Language::French,
+                "Il faisait beau et les enfants jouaient au bord de la rivière",
+            ),
+            (
+                Language::Spanish,
+                "Hacía buen tiempo y los niños jugaban a la orilla del río",
+            ),
+        ];
+
+        for (language, text) in texts {
+            assert_eq!(language, Language::detect(text));
+        }
+    }
+
+    #[test]
+    fn detect_english_test() {
+        let texts = [
+            "The quick brown fox jumps over the lazy dog",
+            "Pack my box with five dozen liquor jugs",
+            "My wife and kids enjoyed the jazz concert",
+            "I love pizza",
+            "Where is the nearest train station",
+            "Please send me the report by Friday",
+            "My phone battery is almost dead",
+            "Turn left at the next corner",
+        ];
+
+        for text in texts {
+            assert_eq!(Language::English, Language::detect(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn quadgrams_prefer_their_language() {
+        let german = "Sie gingen zusammen durch den dunklen Wald nach Hause";
+        let french = "Ils sont rentrés ensemble à la maison par la forêt";
+
+        assert!(
+            Language::German.quadgrams().score(german) > Language::French.quadgrams().score(german)
+        );
+        assert!(
+            Language::French.quadgrams().score(&fold_diacritics(french))
+                > Language::German.quadgrams().score(&fold_diacritics(french))
+        );
+    }
+
+    #[test]
+    fn fold_test() {
+        assert_eq!(
+            "Aerger, Oeuvre, Strasse",
+            fold_diacritics("Ärger, Œuvre, Straße")
+        );
+        assert_eq!("Привет", fold_diacritics("Привет"));
+    }
+}
@@ -5,8 +5,22 @@
 //!
 //! ```toml
 //! [dependencies]
-//! cienli = { version = "0.3.1", default-features = false, features = ["caesar"]}
+//! cienli = { version = "0.4.0", default-features = false, features = ["caesar"]}
 //! ```
 //!
+#[cfg(feature = "analysis")]
+pub mod analysis;
 pub mod ciphers;
 pub mod common;
+mod error;
+#[cfg(feature = "identify")]
+pub mod identify;
+#[cfg(feature = "language")]
+pub mod language;
+#[cfg(feature = "magic")]
+pub mod magic;
+pub mod stream;
+
+pub use error::Error;
+#[cfg(feature = "identify")]
+pub use identify::identify;
@@ -0,0 +1,486 @@
+//! Automatic decoding of a text enciphered or encoded several times over.
+//!
+//! Every keyless transform of the crate (the ROT ciphers, Atbash, the Bacon and Polybius square
+//! ciphers, hex and base64) and the most promising keys of the brute-forceable ones (Caesar,
+//! Affine, rail fence, scytale and single-byte XOR) are applied to the input, then to the most
+//! readable results, breadth-first, up to a given depth.
+//!
+//! The module is behind the `magic` feature, which is not a default feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cienli = { version = "0.4.0", features = ["magic"]}
+//! ```
+use crate::analysis::{
+    chi_squared_counts, english_score, letters, rank, Candidate, NgramModel, ENGLISH_FREQUENCIES,
+};
+use crate::ciphers::affine::Affine;
+use crate::ciphers::atbash::Atbash;
+use crate::ciphers::bacon::Bacon;
+use crate::ciphers::caesar::Caesar;
+use crate::ciphers::polybius_square::PolybiusSquare;
+use crate::ciphers::rail_fence::RailFence;
+use crate::ciphers::rot::{Rot, RotType};
+use crate::ciphers::scytale::Scytale;
+use crate::ciphers::xor::Xor;
+use crate::common::{base64_decode, hex_decode};
+use crate::Error;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+/// How much the score of a text matching the flag pattern is raised.
+const FLAG_BONUS: f64 = 100.0;
+
+/// The largest number of rails of the rail fence and of columns of the scytale tried.
+const MAX_TRANSPOSITION_KEY: usize = 10;
+
+/// The number of bytes of the data the keys of the Caesar, Affine and Xor steps are ranked on.
+const SAMPLE_LENGTH: usize = 128;
+
+/// The settings of [`decode_with`]
+///
+/// The keys of the Caesar, Affine and Xor steps are ranked on the first bytes of the data and
+/// only the best ones are applied to the whole data. The results of each depth are ranked by
+/// score and only the best ones of each kind of step (and every successful hex, base64, Bacon
+/// or Polybius square decoding) are transformed further.
+#[derive(Debug, Clone)]
+pub struct MagicOptions {
+    /// The largest number of transforms chained.
+    pub depth: usize,
+    /// The number of keys of each kind of step applied to every text, and the number of results
+    /// of each kind of step transformed further at each depth.
+    pub width: usize,
+    /// The number of decodings returned.
+    pub results: usize,
+    /// A regular expression matching the plaintext, like the format of a CTF flag, in the
+    /// syntax of the regex crate.
+    pub flag: Option<String>,
+}
+
+impl Default for MagicOptions {
+    fn default() -> Self {
+        MagicOptions {
+            depth: 3,
+            width: 3,
+            results: 10,
+            flag: None,
+        }
+    }
+}
+
+/// A transform of a decoding chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    Rot5,
+    Rot13,
+    Rot18,
+    Rot47,
+    Atbash,
+    /// Caesar decipher with the rotation.
+    Caesar(u8),
+    /// Affine decipher with the key.
+    Affine(u16, u16),
+    /// Rail fence decipher with the number of rails.
+    RailFence(usize),
+    /// Scytale decipher with the key.
+    Scytale(usize),
+    /// Bacon decipher with the two letters.
+    Bacon(char, char),
+    PolybiusSquare,
+    Hex,
+    Base64,
+    /// Xor with the single-byte key.
+    Xor(u8),
+}
+
+impl Step {
+    /// Applies the step to the data, returns None if the step does not apply to the data.
+    ///
+    /// The steps other than Hex, Base64 and Xor only apply to UTF-8 text.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::magic::Step;
+    ///
+    /// assert_eq!(Some(b"Hello".to_vec()), Step::Base64.apply(b"SGVsbG8="));
+    /// assert_eq!(Some(b"Hello".to_vec()), Step::Caesar(3).apply(b"Khoor"));
+    /// assert_eq!(None, Step::Hex.apply(b"Hello"));
+    /// ```
+    pub fn apply(&self, data: &[u8]) -> Option<Vec<u8>> {
+        let text = || std::str::from_utf8(data).ok();
+        let compact = || {
+            text().map(|text| {
+                text.chars()
+                    .filter(|character| !character.is_whitespace())
+                    .collect::<String>()
+            })
+        };
+
+        let result = match *self {
+            Step::Rot5 => Rot::new(RotType::Rot5).decipher(text()?),
+            Step::Rot13 => Rot::new(RotType::Rot13).decipher(text()?),
+            Step::Rot18 => Rot::new(RotType::Rot18).decipher(text()?),
+            Step::Rot47 => Rot::new(RotType::Rot47).decipher(text()?),
+            Step::Atbash => Atbash::new().decipher(text()?),
+            Step::Caesar(rotation) => Caesar::new(rotation).ok()?.decipher(text()?),
+            Step::Affine(a, b) => Affine::new((a, b)).ok()?.decipher(text()?),
+            Step::RailFence(rails) => RailFence::new(rails).ok()?.decipher(text()?),
+            Step::Scytale(key) => Scytale::new(key).ok()?.decipher(text()?),
+            Step::Bacon(a, b) => Bacon::new((a, b)).ok()?.decipher(text()?).ok()?,
+            Step::PolybiusSquare => PolybiusSquare::new().decipher(&compact()?).ok()?,
+            Step::Hex => return hex_decode(&compact()?).ok(),
+            Step::Base64 => return base64_decode(&compact()?).ok(),
+            Step::Xor(key) => return Xor::new([key]).ok().map(|xor| xor.decipher(data)),
+        };
+
+        Some(result.into_bytes())
+    }
+
+    /// Returns whether the step decodes an encoding, whose results are always transformed further.
+    fn decodes(&self) -> bool {
+        matches!(
+            self,
+            Step::Bacon(..) | Step::PolybiusSquare | Step::Hex | Step::Base64
+        )
+    }
+
+    /// Returns every step which may apply to the data.
+    fn candidates(data: &[u8]) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let text = std::str::from_utf8(data).ok();
+
+        if let Some(text) = text {
+            let mut symbols = text
+                .chars()
+                .filter(|character| !character.is_whitespace())
+                .collect::<Vec<char>>();
+            symbols.sort_unstable();
+            symbols.dedup();
+
+            if let [a, b] = symbols[..] {
+                if !a.is_control() && !b.is_control() {
+                    steps.extend([Step::Bacon(a, b), Step::Bacon(b, a)]);
+                }
+            }
+            if symbols.iter().all(char::is_ascii_digit) {
+                steps.push(Step::PolybiusSquare);
+            }
+        }
+
+        steps.extend([Step::Hex, Step::Base64]);
+        steps.extend((1..=255).map(Step::Xor));
+
+        let text = match text {
+            Some(text) => text,
+            None => return steps,
+        };
+
+        steps.extend([
+            Step::Rot5,
+            Step::Rot13,
+            Step::Rot18,
+            Step::Rot47,
+            Step::Atbash,
+        ]);
+        steps.extend((1..26).map(Step::Caesar));
+        for a in [3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25] {
+            steps.extend((0..26).map(|b| Step::Affine(a, b)));
+        }
+
+        let length = text.chars().count();
+        for key in (2..=MAX_TRANSPOSITION_KEY).take_while(|key| *key < length) {
+            steps.extend([Step::RailFence(key), Step::Scytale(key)]);
+        }
+
+        steps
+    }
+}
+
+/// Returns the most readable decodings of the input, with the default options.
+///
+/// # Example:
+/// ```
+/// use cienli::ciphers::caesar::Caesar;
+/// use cienli::common::base64_encode;
+/// use cienli::magic::{decode, Step};
+///
+/// let message = "Meet me by the old lighthouse at midnight";
+/// let cipher = base64_encode(Caesar::new(7).unwrap().encipher(message).as_bytes());
+///
+/// let decodings = decode(&cipher);
+/// assert_eq!(vec![Step::Base64, Step::Caesar(7)], decodings[0].key);
+/// assert_eq!(message, decodings[0].plaintext);
+/// ```
+pub fn decode(input: &str) -> Vec<Candidate<Vec<Step>>> {
+    decode_with(input, &MagicOptions::default()).expect("The default options have no flag")
+}
+
+/// Returns the most readable decodings of the input, from the best to the worst, the key
+/// of each decoding is its chain of steps.
+///
+/// The score is the english quadgram score of the plaintext, raised by 100 when the plaintext
+/// matches the flag pattern. Only the UTF-8 plaintexts are returned, and every plaintext is only
+/// returned once, with its shortest chain.
+///
+/// # Example:
+/// ```
+/// use cienli::ciphers::xor::Xor;
+/// use cienli::magic::{decode_with, MagicOptions, Step};
+///
+/// let flag = "flag{xor_me}";
+/// let cipher = Xor::new([0x2a]).unwrap().encipher_hex(flag);
+///
+/// let options = MagicOptions {
+///     depth: 2,
+///     flag: Some(String::from(r"flag\{\w+\}")),
+///     ..MagicOptions::default()
+/// };
+/// let decodings = decode_with(&cipher, &options).unwrap();
+///
+/// assert_eq!(vec![Step::Hex, Step::Xor(0x2a)], decodings[0].key);
+/// assert_eq!(flag, decodings[0].plaintext);
+/// ```
+///
+/// # Errors:
+/// The flag must be a valid regular expression.
+pub fn decode_with(
+    input: &str,
+    options: &MagicOptions,
+) -> Result<Vec<Candidate<Vec<Step>>>, Error> {
+    let flag = options
+        .flag
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|_| Error::InvalidKey("The flag is not a valid regular expression"))?;
+
+    let model = NgramModel::english_quadgrams();
+    let score = |text: &str| readability(text, model, flag.as_ref());
+
+    let mut seen = HashSet::new();
+    seen.insert(input.as_bytes().to_vec());
+
+    let mut decodings = Vec::new();
+    let mut frontier = vec![(Vec::new(), input.as_bytes().to_vec())];
+
+    for _ in 0..options.depth {
+        let mut scored = Vec::new();
+        let mut decoded = Vec::new();
+
+        for (chain, data) in frontier.iter() {
+            for step in promising(data, options.width, flag.as_ref()) {
+                let result = match step.apply(data) {
+                    Some(result) if seen.insert(result.clone()) => result,
+                    _ => continue,
+                };
+
+                let mut chain = chain.clone();
+                chain.push(step);
+
+                let text = std::str::from_utf8(&result).ok();
+                let candidate_score = text.map_or(f64::NEG_INFINITY, score);
+                if let Some(text) = text {
+                    decodings.push(Candidate {
+                        key: chain.clone(),
+                        plaintext: text.to_string(),
+                        score: candidate_score,
+                    });
+                }
+
+                match step.decodes() {
+                    true => decoded.push((chain, result)),
+                    false => scored.push((candidate_score, chain, result)),
+                }
+            }
+        }
+
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut kept = HashMap::new();
+        frontier = decoded;
+        frontier.extend(
+            scored
+                .into_iter()
+                .filter(|(_, chain, _)| {
+                    let count = kept
+                        .entry(mem::discriminant(&chain[chain.len() - 1]))
+                        .or_insert(0);
+                    *count += 1;
+                    *count <= options.width
+                })
+                .map(|(_, chain, result)| (chain, result)),
+        );
+    }
+
+    rank(&mut decodings);
+    decodings.truncate(options.results);
+    Ok(decodings)
+}
+
+/// Returns the steps applied to the data: every step but the Caesar, Affine and Xor ones, whose
+/// keys are ranked by the english score of the first bytes of their results (raised when they
+/// match the flag pattern), only the best ones are kept.
+fn promising(data: &[u8], width: usize, flag: Option<&Regex>) -> Vec<Step> {
+    let mut end = data.len().min(SAMPLE_LENGTH);
+    if let Ok(text) = std::str::from_utf8(data) {
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+    }
+    let sample = &data[..end];
+
+    let mut steps = Vec::new();
+    let mut keyed: [Vec<(f64, Step)>; 3] = Default::default();
+
+    for step in Step::candidates(data) {
+        let family = match step {
+            Step::Caesar(_) => 0,
+            Step::Affine(..) => 1,
+            Step::Xor(_) => 2,
+            _ => {
+                steps.push(step);
+                continue;
+            }
+        };
+
+        let preview = step.apply(sample);
+        let preview_score = match preview.as_deref().and_then(text_prefix) {
+            Some(text) if flag.is_some_and(|flag| flag.is_match(text)) => {
+                english_score(text) + FLAG_BONUS
+            }
+            Some(text) => english_score(text),
+            None => f64::NEG_INFINITY,
+        };
+
+        keyed[family].push((preview_score, step));
+    }
+
+    for mut ranked in keyed {
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        steps.extend(ranked.into_iter().take(width).map(|(_, step)| step));
+    }
+
+    steps
+}
+
+/// Returns the text of the data, without the character cut at its end by the sampling.
+fn text_prefix(data: &[u8]) -> Option<&str> {
+    match std::str::from_utf8(data) {
+        Ok(text) => Some(text),
+        Err(error) if error.error_len().is_none() => {
+            std::str::from_utf8(&data[..error.valid_up_to()]).ok()
+        }
+        Err(_) => None,
+    }
+}
+
+/// Returns how readable a text is: its quadgram score, lowered by how far its letter
+/// frequencies are from english and by the share of characters which are not letters,
+/// and raised when it matches the flag pattern.
+fn readability(text: &str, model: &NgramModel, flag: Option<&Regex>) -> f64 {
+    let positions = letters(text, model.alphabet()).collect::<Vec<usize>>();
+    let mut counts = vec![0; model.alphabet().len()];
+    for position in positions.iter() {
+        counts[*position] += 1;
+    }
+
+    let letters = positions.len();
+    let total = text
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .count();
+
+    let mut score = match letters {
+        0 => f64::NEG_INFINITY,
+        _ => {
+            model.score_letters(&positions)
+                - chi_squared_counts(&counts, &ENGLISH_FREQUENCIES) / letters as f64
+                + 2.0 * (letters as f64 / total as f64).log10()
+        }
+    };
+
+    if flag.is_some_and(|flag| flag.is_match(text)) {
+        score = score.max(-FLAG_BONUS) + FLAG_BONUS;
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decode_with, MagicOptions, Step};
+    use crate::ciphers::bacon::Bacon;
+    use crate::ciphers::polybius_square::PolybiusSquare;
+    use crate::ciphers::rail_fence::RailFence;
+    use crate::ciphers::rot::{Rot, RotType};
+
+    const MESSAGE: &str = "the treasure is buried under the old oak tree";
+
+    #[test]
+    fn keyless_test() {
+        let rot13 = Rot::new(RotType::Rot13).encipher(MESSAGE);
+        let decodings = decode(&rot13);
+
+        assert_eq!(vec![Step::Rot13], decodings[0].key);
+        assert_eq!(MESSAGE, decodings[0].plaintext);
+    }
+
+    #[test]
+    fn encodings_test() {
+        let bacon = Bacon::new(('a', 'b')).unwrap().encipher(MESSAGE);
+        let polybius = PolybiusSquare::new().encipher(&bacon);
+
+        let decodings = decode(&polybius);
+        assert_eq!(
+            vec![Step::PolybiusSquare, Step::Bacon('A', 'B')],
+            decodings[0].key
+        );
+        assert_eq!(
+            MESSAGE.replace(' ', "").to_uppercase(),
+            decodings[0].plaintext
+        );
+    }
+
+    #[test]
+    fn transposition_test() {
+        let cipher = RailFence::new(4).unwrap().encipher(MESSAGE);
+        let decodings = decode(&cipher);
+
+        assert_eq!(vec![Step::RailFence(4)], decodings[0].key);
+    }
+
+    #[test]
+    fn flag_test() {
+        let options = MagicOptions {
+            depth: 1,
+            flag: Some(String::from(r"CTF\{[a-z_]+\}")),
+            ..MagicOptions::default()
+        };
+        let decodings = decode_with("PGS{mvc_mnc}", &options).unwrap();
+
+        assert_eq!(vec![Step::Rot13], decodings[0].key);
+        assert_eq!("CTF{zip_zap}", decodings[0].plaintext);
+        assert!(decodings[0].score > 0.0);
+    }
+
+    #[test]
+    fn options_test() {
+        let options = MagicOptions {
+            depth: 0,
+            ..MagicOptions::default()
+        };
+        assert!(decode_with("Uryyb", &options).unwrap().is_empty());
+
+        let options = MagicOptions {
+            results: 3,
+            ..MagicOptions::default()
+        };
+        assert_eq!(3, decode_with("Uryyb", &options).unwrap().len());
+
+        let options = MagicOptions {
+            flag: Some(String::from("CTF{")),
+            ..MagicOptions::default()
+        };
+        assert!(decode_with("Uryyb", &options).is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! Streaming encipher/decipher over [`std::io::Read`] and [`std::io::Write`].
+//!
+//! The stream-friendly ciphers implement [`StreamCipher`], so they can wrap any reader
+//! or writer and process it chunk by chunk with constant memory:
+//!
+//! ```
+//! use cienli::ciphers::caesar::Caesar;
+//! use cienli::stream::{CipherReader, Direction};
+//! use std::io::Read;
+//!
+//! let caesar = Caesar::new(5).unwrap();
+//! let mut reader = CipherReader::new("Hello, This Is A Test".as_bytes(), caesar, Direction::Encipher);
+//!
+//! let mut cipher = String::new();
+//! reader.read_to_string(&mut cipher).unwrap();
+//! assert_eq!("Mjqqt, Ymnx Nx F Yjxy", cipher);
+//! ```
+use std::io::{self, ErrorKind, Read, Write};
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Direction of a stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Encipher,
+    Decipher,
+}
+
+/// A cipher that can be applied on a stream chunk by chunk
+///
+/// The position is the state kept between the chunks (e.g. the position in the key),
+/// it starts at zero and is only updated by the cipher.
+pub trait StreamCipher {
+    /// Transforms a chunk of input into the output and returns the number of consumed bytes.
+    ///
+    /// The bytes that are not consumed (e.g. an incomplete UTF-8 sequence at the end of the chunk)
+    /// are given back at the beginning of the next chunk.
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        position: &mut usize,
+        direction: Direction,
+    ) -> usize;
+}
+
+impl<C: StreamCipher + ?Sized> StreamCipher for &C {
+    fn transform(
+        &self,
+        input: &[u8],
+        output: &mut Vec<u8>,
+        position: &mut usize,
+        direction: Direction,
+    ) -> usize {
+        (**self).transform(input, output, position, direction)
+    }
+}
+
+/// Transforms the characters of a chunk of UTF-8 text, invalid bytes are copied as they are
+/// and an incomplete sequence at the end of the chunk is not consumed.
+// Unused when all the text stream ciphers are disabled.
+#[allow(dead_code)]
+pub(crate) fn transform_chars<F>(input: &[u8], output: &mut Vec<u8>, mut transform: F) -> usize
+where
+    F: FnMut(char) -> char,
+{
+    let mut consumed = 0;
+    let mut buffer = [0; 4];
+
+    while consumed < input.len() {
+        let (text, invalid_len) = match std::str::from_utf8(&input[consumed..]) {
+            Ok(text) => (text, Some(0)),
+            Err(error) => {
+                let valid = &input[consumed..consumed + error.valid_up_to()];
+                (
+                    std::str::from_utf8(valid).unwrap_or_default(),
+                    error.error_len(),
+                )
+            }
+        };
+
+        for character in text.chars() {
+            output.extend_from_slice(transform(character).encode_utf8(&mut buffer).as_bytes());
+        }
+        consumed += text.len();
+
+        match invalid_len {
+            Some(len) => {
+                output.extend_from_slice(&input[consumed..consumed + len]);
+                consumed += len;
+            }
+            None => break,
+        }
+    }
+
+    consumed
+}
+
+/// A reader that enciphers or deciphers the data of an inner reader
+///
+/// The struct is generated through the new() function.
+pub struct CipherReader<R, C> {
+    inner: R,
+    cipher: C,
+    direction: Direction,
+    position: usize,
+    pending: Vec<u8>,
+    output: Vec<u8>,
+    output_position: usize,
+    eof: bool,
+}
+
+impl<R: Read, C: StreamCipher> CipherReader<R, C> {
+    /// Initialize a cipher reader with an inner reader, a cipher and a direction.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::vigenere::Vigenere;
+    /// use cienli::stream::{CipherReader, Direction};
+    /// use std::io::Read;
+    ///
+    /// let vigenere = Vigenere::new("ABCDE").unwrap();
+    /// let mut reader = CipherReader::new("Qxgux :)".as_bytes(), &vigenere, Direction::Decipher);
+    ///
+    /// let mut message = String::new();
+    /// reader.read_to_string(&mut message).unwrap();
+    /// assert_eq!("Qwert :)", message);
+    /// ```
+    pub fn new(inner: R, cipher: C, direction: Direction) -> CipherReader<R, C> {
+        CipherReader {
+            inner,
+            cipher,
+            direction,
+            position: 0,
+            pending: Vec::new(),
+            output: Vec::new(),
+            output_position: 0,
+            eof: false,
+        }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn fill_output(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.output_position = 0;
+
+        let mut chunk = [0; CHUNK_SIZE];
+        let read = self.inner.read(&mut chunk)?;
+
+        if read == 0 {
+            self.eof = true;
+            self.output.append(&mut self.pending);
+            return Ok(());
+        }
+
+        self.pending.extend_from_slice(&chunk[..read]);
+        let consumed = self.cipher.transform(
+            &self.pending,
+            &mut self.output,
+            &mut self.position,
+            self.direction,
+        );
+        self.pending.drain(..consumed);
+
+        Ok(())
+    }
+}
+
+impl<R: Read, C: StreamCipher> Read for CipherReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output_position == self.output.len() {
+            if self.eof {
+                return Ok(0);
+            }
+            self.fill_output()?;
+        }
+
+        let available = &self.output[self.output_position..];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.output_position += len;
+
+        Ok(len)
+    }
+}
+
+/// A writer that enciphers or deciphers the data before writing it to an inner writer
+///
+/// The struct is generated through the new() function, the data that is kept back
+/// (an incomplete UTF-8 sequence) is written when the writer is finished or dropped.
+pub struct CipherWriter<W: Write, C: StreamCipher> {
+    inner: Option<W>,
+    cipher: C,
+    direction: Direction,
+    position: usize,
+    pending: Vec<u8>,
+}
+
+impl<W: Write, C: StreamCipher> CipherWriter<W, C> {
+    /// Initialize a cipher writer with an inner writer, a cipher and a direction.
+    ///
+    /// # Example:
+    /// ```
+    /// use cienli::ciphers::xor::Xor;
+    /// use cienli::stream::{CipherWriter, Direction};
+    /// use std::io::Write;
+    ///
+    /// let xor = Xor::new("VMMN8").unwrap();
+    /// let mut writer = CipherWriter::new(Vec::new(), xor, Direction::Encipher);
+    ///
+    /// writer.write_all(b"jp1").unwrap();
+    /// writer.write_all(b"4N").unwrap();
+    /// assert_eq!(b"<=|zv".to_vec(), writer.finish().unwrap());
+    /// ```
+    pub fn new(inner: W, cipher: C, direction: Direction) -> CipherWriter<W, C> {
+        CipherWriter {
+            inner: Some(inner),
+            cipher,
+            direction,
+            position: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Writes the data kept back, flushes and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_pending()?;
+
+        let mut inner = self
+            .inner
+            .take()
+            .ok_or_else(|| io::Error::new(ErrorKind::Other, "The writer is already finished"))?;
+        inner.flush()?;
+
+        Ok(inner)
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.write_all(&self.pending)?;
+            self.pending.clear();
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write, C: StreamCipher> Write for CipherWriter<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let inner = self
+            .inner
+            .as_mut()
+            .ok_or_else(|| io::Error::new(ErrorKind::Other, "The writer is already finished"))?;
+
+        self.pending.extend_from_slice(buf);
+
+        let mut output = Vec::with_capacity(self.pending.len());
+        let consumed = self.cipher.transform(
+            &self.pending,
+            &mut output,
+            &mut self.position,
+            self.direction,
+        );
+        self.pending.drain(..consumed);
+        inner.write_all(&output)?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.inner.as_mut() {
+            Some(inner) => inner.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write, C: StreamCipher> Drop for CipherWriter<W, C> {
+    fn drop(&mut self) {
+        let _ = self.write_pending();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{transform_chars, CipherReader, CipherWriter, Direction, StreamCipher};
+    use std::io::{Read, Write};
+
+    struct Upper;
+
+    impl StreamCipher for Upper {
+        fn transform(
+            &self,
+            input: &[u8],
+            output: &mut Vec<u8>,
+            position: &mut usize,
+            _direction: Direction,
+        ) -> usize {
+            transform_chars(input, output, |character| {
+                *position += 1;
+                character.to_ascii_uppercase()
+            })
+        }
+    }
+
+    /// A reader that returns one byte at a time, to split the UTF-8 sequences.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn transform_chars_keeps_incomplete_sequence() {
+        let mut output = Vec::new();
+        let input = "aé".as_bytes();
+
+        assert_eq!(1, transform_chars(&input[..2], &mut output, |c| c));
+        assert_eq!(b"a".to_vec(), output);
+    }
+
+    #[test]
+    fn transform_chars_copies_invalid_bytes() {
+        let mut output = Vec::new();
+
+        assert_eq!(
+            3,
+            transform_chars(&[b'a', 0xff, b'b'], &mut output, |c| {
+                c.to_ascii_uppercase()
+            })
+        );
+        assert_eq!(vec![b'A', 0xff, b'B'], output);
+    }
+
+    #[test]
+    fn reader_split_sequences() {
+        let mut reader =
+            CipherReader::new(Trickle("hé • 😀 ok".as_bytes()), Upper, Direction::Encipher);
+
+        let mut result = String::new();
+        reader.read_to_string(&mut result).unwrap();
+        assert_eq!("Hé • 😀 OK", result);
+    }
+
+    #[test]
+    fn reader_incomplete_tail() {
+        let mut reader = CipherReader::new(&[b'a', 0xe2, 0x80][..], Upper, Direction::Encipher);
+
+        let mut result = Vec::new();
+        reader.read_to_end(&mut result).unwrap();
+        assert_eq!(vec![b'A', 0xe2, 0x80], result);
+    }
+
+    #[test]
+    fn writer_split_sequences() {
+        let mut writer = CipherWriter::new(Vec::new(), Upper, Direction::Encipher);
+
+        for byte in "hé • ok".as_bytes() {
+            writer.write_all(&[*byte]).unwrap();
+        }
+        assert_eq!("Hé • OK".as_bytes(), writer.finish().unwrap());
+    }
+}